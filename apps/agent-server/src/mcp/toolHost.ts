@@ -1096,7 +1096,8 @@ async function runPantherEyesScanCli(input: {
   ];
 
   const { stdout, stderr, exitCode } = await spawnAndCollect('cargo', args, input.cwd);
-  if (exitCode !== 0) {
+  // Exit code 1 is a completed scan with status "block"; the JSON report is still on stdout.
+  if (exitCode !== 0 && exitCode !== 1) {
     throw new Error(
       `panthereyes.scan failed (cargo exit ${exitCode}). stderr: ${stderr.trim() || '<empty>'}`,
     );
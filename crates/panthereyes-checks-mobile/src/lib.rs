@@ -1,23 +1,48 @@
-use std::fs;
-use std::path::{Path, PathBuf};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
 
 use panthereyes_core::{
-    EngineError, Finding, ScanEngine, ScanReport, ScanRequest, Severity, TargetKind,
+    run_workspace_checks, Check, CheckContext, CheckDiagnostic, CheckMetadata, CheckRegistry,
+    EngineError, Finding, ScanEngine, ScanReport, ScanRequest, Severity, TargetKind, Workspace,
 };
 
+#[derive(Clone)]
 pub struct MobileChecksEngine {
-    checks: Vec<Box<dyn MobileCheck>>,
+    checks: Vec<Arc<dyn Check>>,
 }
 
 impl Default for MobileChecksEngine {
     fn default() -> Self {
-        Self {
-            checks: vec![
-                Box::new(IosAtsArbitraryLoadsCheck),
-                Box::new(AndroidCleartextTrafficCheck),
-                Box::new(AndroidDebuggableCheck),
-            ],
-        }
+        Self::empty()
+            .with_check(IosAtsArbitraryLoadsCheck)
+            .with_check(AndroidCleartextTrafficCheck)
+            .with_check(AndroidDebuggableCheck)
+    }
+}
+
+impl MobileChecksEngine {
+    /// Engine without any check, to assemble a custom check list.
+    pub fn empty() -> Self {
+        Self { checks: Vec::new() }
+    }
+
+    pub fn with_check(mut self, check: impl Check + 'static) -> Self {
+        self.checks.push(Arc::new(check));
+        self
+    }
+
+    /// Adds the registry checks that report iOS, Android or mobile findings.
+    pub fn with_registry(mut self, registry: &CheckRegistry) -> Self {
+        self.checks
+            .extend(registry.checks_for(TargetKind::Mobile).cloned());
+        self
+    }
+
+    /// Drops the checks matching a finding id or catalog rule id.
+    pub fn without_check(mut self, id: &str) -> Self {
+        self.checks.retain(|check| !check.metadata().implements(id));
+        self
     }
 }
 
@@ -26,11 +51,23 @@ impl ScanEngine for MobileChecksEngine {
         "panthereyes-checks-mobile"
     }
 
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
     fn target(&self) -> TargetKind {
         TargetKind::Mobile
     }
 
     fn scan(&self, request: &ScanRequest) -> Result<ScanReport, EngineError> {
+        self.scan_workspace(request, &request.discover_workspace()?)
+    }
+
+    fn scan_workspace(
+        &self,
+        request: &ScanRequest,
+        workspace: &Workspace,
+    ) -> Result<ScanReport, EngineError> {
         if request.target != TargetKind::Mobile {
             return Err(EngineError::InvalidRequest(format!(
                 "mobile engine only supports target '{}', got '{}'",
@@ -39,18 +76,34 @@ impl ScanEngine for MobileChecksEngine {
             )));
         }
 
-        let mut report = ScanReport::new(TargetKind::Mobile);
-        let ctx = MobileScanContext::new(request.path.clone())?;
+        run_workspace_checks(TargetKind::Mobile, &self.checks, request, workspace)
+    }
 
-        for check in &self.checks {
-            check.run(&ctx, &mut report)?;
-        }
+    fn checks(&self) -> Vec<CheckMetadata> {
+        self.checks.iter().map(|check| *check.metadata()).collect()
+    }
 
-        report.recompute_status();
-        Ok(report)
+    fn applies_to(&self, workspace: &Workspace) -> bool {
+        workspace.files().iter().any(|file| {
+            file.file_name().is_some_and(|name| {
+                MOBILE_PROJECT_FILES
+                    .iter()
+                    .any(|marker| name.eq_ignore_ascii_case(marker))
+            })
+        })
     }
 }
 
+/// Files that mark an iOS or Android project for `--target auto`.
+const MOBILE_PROJECT_FILES: &[&str] = &[
+    "Info.plist",
+    "project.pbxproj",
+    "Podfile",
+    "AndroidManifest.xml",
+    "build.gradle",
+    "build.gradle.kts",
+];
+
 pub fn scan_mobile_path(path: impl AsRef<Path>) -> Result<ScanReport, EngineError> {
     let request = ScanRequest::new(TargetKind::Mobile, path.as_ref());
     MobileChecksEngine::default().scan(&request)
@@ -60,104 +113,52 @@ pub fn scan_mobile_path(path: impl AsRef<Path>) -> Result<ScanReport, EngineErro
 pub fn run_demo_mobile_checks(path: &str) -> ScanReport {
     scan_mobile_path(path).unwrap_or_else(|err| {
         let mut report = ScanReport::new(TargetKind::Mobile);
-        report.push(Finding::new(
-            "mobile.engine.error",
-            "Falha ao executar checks mobile",
-            Severity::High,
-            TargetKind::Mobile,
-            err.to_string(),
-            "Revise permissões de leitura e o caminho informado.",
+        report.push_diagnostic(CheckDiagnostic::from_error(
+            "panthereyes-checks-mobile",
+            &err,
         ));
         report
     })
 }
 
-trait MobileCheck {
-    fn run(&self, ctx: &MobileScanContext, report: &mut ScanReport) -> Result<(), EngineError>;
-}
-
-struct MobileScanContext {
-    root: PathBuf,
-    files: Vec<PathBuf>,
+pub struct IosAtsArbitraryLoadsCheck;
+
+impl IosAtsArbitraryLoadsCheck {
+    const METADATA: CheckMetadata = CheckMetadata {
+        id: "mobile.ios.ats.arbitrary-loads-enabled",
+        rule_id: "mobile.transport.cleartext-disabled",
+        title: "ATS permite carregamentos arbitrários",
+        description: "Info.plist nao deve habilitar NSAllowsArbitraryLoads.",
+        target: TargetKind::Ios,
+        default_severity: Severity::High,
+        tags: &["mobile", "ios", "ats", "transport"],
+        allow_exception: true,
+    };
 }
 
-impl MobileScanContext {
-    fn new(root: PathBuf) -> Result<Self, EngineError> {
-        if !root.exists() {
-            return Err(EngineError::InvalidRequest(format!(
-                "scan path not found: {}",
-                root.display()
-            )));
-        }
-
-        let mut files = Vec::new();
-        collect_files(&root, &mut files)?;
-        Ok(Self { root, files })
+impl Check for IosAtsArbitraryLoadsCheck {
+    fn metadata(&self) -> &CheckMetadata {
+        &Self::METADATA
     }
 
-    fn find_by_name(&self, file_name: &str) -> Vec<&PathBuf> {
-        self.files
-            .iter()
-            .filter(|path| {
-                path.file_name()
-                    .and_then(|value| value.to_str())
-                    .is_some_and(|value| value.eq_ignore_ascii_case(file_name))
-            })
-            .collect()
+    fn is_file_local(&self) -> bool {
+        true
     }
 
-    fn read_to_string(&self, path: &Path) -> Result<String, EngineError> {
-        fs::read_to_string(path).map_err(|source| EngineError::io(path.to_path_buf(), source))
-    }
-
-    fn relative_path(&self, path: &Path) -> PathBuf {
-        path.strip_prefix(&self.root)
-            .map_or_else(|_| path.to_path_buf(), Path::to_path_buf)
-    }
-}
-
-fn collect_files(root: &Path, out: &mut Vec<PathBuf>) -> Result<(), EngineError> {
-    if root.is_file() {
-        out.push(root.to_path_buf());
-        return Ok(());
-    }
-
-    let entries =
-        fs::read_dir(root).map_err(|source| EngineError::io(root.to_path_buf(), source))?;
-    for entry in entries {
-        let entry = entry.map_err(|source| EngineError::io(root.to_path_buf(), source))?;
-        let path = entry.path();
-        let file_type = entry
-            .file_type()
-            .map_err(|source| EngineError::io(path.clone(), source))?;
-
-        if file_type.is_dir() {
-            collect_files(&path, out)?;
-        } else if file_type.is_file() {
-            out.push(path);
-        }
-    }
-
-    Ok(())
-}
-
-struct IosAtsArbitraryLoadsCheck;
-
-impl MobileCheck for IosAtsArbitraryLoadsCheck {
-    fn run(&self, ctx: &MobileScanContext, report: &mut ScanReport) -> Result<(), EngineError> {
+    fn run(&self, ctx: &CheckContext<'_>, report: &mut ScanReport) -> Result<(), EngineError> {
         for plist_path in ctx.find_by_name("Info.plist") {
-            let raw = ctx.read_to_string(plist_path)?;
-            if contains_ios_ats_arbitrary_loads_enabled(&raw) {
+            let Some(raw) = ctx.read_file(plist_path)? else {
+                continue;
+            };
+            if let Some(range) = find_ios_ats_arbitrary_loads_enabled(&raw) {
                 report.push(
-                    Finding::new(
-                        "mobile.ios.ats.arbitrary-loads-enabled",
-                        "ATS permite carregamentos arbitrários",
-                        Severity::High,
-                        TargetKind::Ios,
+                    Finding::for_check(
+                        &Self::METADATA,
                         "NSAllowsArbitraryLoads=true detectado no Info.plist.",
                         "Desabilite NSAllowsArbitraryLoads e configure exceções ATS específicas por domínio.",
                     )
-                    .with_file(ctx.relative_path(plist_path)),
+                    .with_file(&plist_path.relative)
+                    .with_match(&raw, range),
                 );
             }
         }
@@ -165,23 +166,44 @@ impl MobileCheck for IosAtsArbitraryLoadsCheck {
     }
 }
 
-struct AndroidCleartextTrafficCheck;
+pub struct AndroidCleartextTrafficCheck;
+
+impl AndroidCleartextTrafficCheck {
+    const METADATA: CheckMetadata = CheckMetadata {
+        id: "mobile.android.cleartext-traffic-enabled",
+        rule_id: "mobile.transport.cleartext-disabled",
+        title: "Cleartext traffic habilitado",
+        description: "AndroidManifest.xml nao deve habilitar usesCleartextTraffic.",
+        target: TargetKind::Android,
+        default_severity: Severity::High,
+        tags: &["mobile", "android", "network", "cleartext"],
+        allow_exception: true,
+    };
+}
 
-impl MobileCheck for AndroidCleartextTrafficCheck {
-    fn run(&self, ctx: &MobileScanContext, report: &mut ScanReport) -> Result<(), EngineError> {
+impl Check for AndroidCleartextTrafficCheck {
+    fn metadata(&self) -> &CheckMetadata {
+        &Self::METADATA
+    }
+
+    fn is_file_local(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &CheckContext<'_>, report: &mut ScanReport) -> Result<(), EngineError> {
         for manifest_path in ctx.find_by_name("AndroidManifest.xml") {
-            let raw = ctx.read_to_string(manifest_path)?;
-            if contains_android_cleartext_enabled(&raw) {
+            let Some(raw) = ctx.read_file(manifest_path)? else {
+                continue;
+            };
+            if let Some(range) = find_android_cleartext_enabled(&raw) {
                 report.push(
-                    Finding::new(
-                        "mobile.android.cleartext-traffic-enabled",
-                        "Cleartext traffic habilitado",
-                        Severity::High,
-                        TargetKind::Android,
+                    Finding::for_check(
+                        &Self::METADATA,
                         "android:usesCleartextTraffic=\\\"true\\\" detectado no AndroidManifest.xml.",
                         "Desabilite cleartext traffic ou restrinja via Network Security Config para domínios específicos.",
                     )
-                    .with_file(ctx.relative_path(manifest_path)),
+                    .with_file(&manifest_path.relative)
+                    .with_match(&raw, range),
                 );
             }
         }
@@ -189,23 +211,44 @@ impl MobileCheck for AndroidCleartextTrafficCheck {
     }
 }
 
-struct AndroidDebuggableCheck;
+pub struct AndroidDebuggableCheck;
+
+impl AndroidDebuggableCheck {
+    const METADATA: CheckMetadata = CheckMetadata {
+        id: "mobile.android.debuggable-enabled",
+        rule_id: "mobile.debug.disabled",
+        title: "Aplicação Android debuggable",
+        description: "AndroidManifest.xml nao deve declarar android:debuggable=true.",
+        target: TargetKind::Android,
+        default_severity: Severity::Medium,
+        tags: &["mobile", "android", "release-hardening"],
+        allow_exception: false,
+    };
+}
+
+impl Check for AndroidDebuggableCheck {
+    fn metadata(&self) -> &CheckMetadata {
+        &Self::METADATA
+    }
+
+    fn is_file_local(&self) -> bool {
+        true
+    }
 
-impl MobileCheck for AndroidDebuggableCheck {
-    fn run(&self, ctx: &MobileScanContext, report: &mut ScanReport) -> Result<(), EngineError> {
+    fn run(&self, ctx: &CheckContext<'_>, report: &mut ScanReport) -> Result<(), EngineError> {
         for manifest_path in ctx.find_by_name("AndroidManifest.xml") {
-            let raw = ctx.read_to_string(manifest_path)?;
-            if contains_android_debuggable_enabled(&raw) {
+            let Some(raw) = ctx.read_file(manifest_path)? else {
+                continue;
+            };
+            if let Some(range) = find_android_debuggable_enabled(&raw) {
                 report.push(
-                    Finding::new(
-                        "mobile.android.debuggable-enabled",
-                        "Aplicação Android debuggable",
-                        Severity::Medium,
-                        TargetKind::Android,
+                    Finding::for_check(
+                        &Self::METADATA,
                         "android:debuggable=\\\"true\\\" detectado no AndroidManifest.xml.",
                         "Garanta builds release com android:debuggable=false e revise configuração por variante.",
                     )
-                    .with_file(ctx.relative_path(manifest_path)),
+                    .with_file(&manifest_path.relative)
+                    .with_match(&raw, range),
                 );
             }
         }
@@ -213,31 +256,47 @@ impl MobileCheck for AndroidDebuggableCheck {
     }
 }
 
-fn contains_android_cleartext_enabled(manifest: &str) -> bool {
-    manifest.contains("usesCleartextTraffic=\"true\"")
-        || manifest.contains("usesCleartextTraffic='true'")
-        || manifest.contains("android:usesCleartextTraffic=\"true\"")
-        || manifest.contains("android:usesCleartextTraffic='true'")
+fn find_android_cleartext_enabled(manifest: &str) -> Option<Range<usize>> {
+    find_first_of(
+        manifest,
+        &[
+            "android:usesCleartextTraffic=\"true\"",
+            "android:usesCleartextTraffic='true'",
+            "usesCleartextTraffic=\"true\"",
+            "usesCleartextTraffic='true'",
+        ],
+    )
 }
 
-fn contains_android_debuggable_enabled(manifest: &str) -> bool {
-    manifest.contains("android:debuggable=\"true\"")
-        || manifest.contains("android:debuggable='true'")
+fn find_android_debuggable_enabled(manifest: &str) -> Option<Range<usize>> {
+    find_first_of(
+        manifest,
+        &["android:debuggable=\"true\"", "android:debuggable='true'"],
+    )
 }
 
-fn contains_ios_ats_arbitrary_loads_enabled(plist: &str) -> bool {
-    let key_pos = plist.find("NSAllowsArbitraryLoads");
-    let Some(key_pos) = key_pos else {
-        return false;
-    };
+fn find_ios_ats_arbitrary_loads_enabled(plist: &str) -> Option<Range<usize>> {
+    let key_pos = plist.find("NSAllowsArbitraryLoads")?;
 
     let tail = &plist[key_pos..];
-    tail.contains("<true/>") || tail.contains("<true />")
+    let value = find_first_of(tail, &["<true/>", "<true />"])?;
+    Some(key_pos..key_pos + value.end)
+}
+
+/// Returns the range of the first needle (in priority order) found in `haystack`.
+fn find_first_of(haystack: &str, needles: &[&str]) -> Option<Range<usize>> {
+    needles.iter().find_map(|needle| {
+        haystack
+            .find(needle)
+            .map(|start| start..start + needle.len())
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::fs;
+    use std::path::PathBuf;
     use std::sync::atomic::{AtomicU64, Ordering};
     use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -319,10 +378,20 @@ mod tests {
 
         assert_eq!(finding.target, TargetKind::Android);
         assert_eq!(finding.severity, Severity::High);
+        assert_eq!(
+            finding.rule_id.as_deref(),
+            Some("mobile.transport.cleartext-disabled")
+        );
         assert_eq!(
             finding.file.as_deref(),
             Some(Path::new("android/app/src/main/AndroidManifest.xml"))
         );
+        let span = finding.span.expect("cleartext finding should carry a span");
+        assert_eq!((span.line, span.column), (3, 32));
+        assert_eq!(
+            finding.evidence.as_deref(),
+            Some(r#"<application android:usesCleartextTraffic="true" />"#)
+        );
 
         fs::remove_dir_all(root).ok();
     }
@@ -345,4 +414,158 @@ mod tests {
 
         fs::remove_dir_all(root).ok();
     }
+
+    #[test]
+    fn unreadable_manifest_does_not_hide_later_ones() {
+        let root = unique_temp_dir("panthereyes-mobile-unreadable");
+        fs::create_dir_all(root.join("a")).expect("create dirs");
+        fs::write(root.join("a/AndroidManifest.xml"), b"<manifest caf\xe9>").expect("write");
+        write_file(
+            &root.join("b/AndroidManifest.xml"),
+            r#"<application android:debuggable="true"></application>"#,
+        );
+
+        let report = scan_mobile_path(&root).expect("scan should succeed");
+        let finding = report
+            .findings
+            .iter()
+            .find(|finding| finding.id == "mobile.android.debuggable-enabled")
+            .expect("the readable manifest is still checked");
+        assert_eq!(
+            finding.file.as_deref(),
+            Some(Path::new("b/AndroidManifest.xml"))
+        );
+        assert_eq!(report.status, panthereyes_core::ScanStatus::Warn);
+        assert!(report.diagnostics.iter().all(|diagnostic| {
+            diagnostic.kind == panthereyes_core::DiagnosticKind::InvalidEncoding
+                && diagnostic.path.as_deref() == Some(Path::new("a/AndroidManifest.xml"))
+        }));
+        assert!(report
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.check == "mobile.android.debuggable-enabled"));
+
+        fs::remove_dir_all(root).ok();
+    }
+
+    #[test]
+    fn honors_inline_suppressions_in_the_manifest() {
+        let root = unique_temp_dir("panthereyes-mobile-suppression");
+        write_file(
+            &root.join("AndroidManifest.xml"),
+            r#"<manifest xmlns:android="http://schemas.android.com/apk/res/android">
+  <!-- panthereyes-ignore mobile.android.debuggable-enabled: flavor interno de QA -->
+  <application
+      android:debuggable="true"
+      android:usesCleartextTraffic="true">
+  </application>
+</manifest>
+"#,
+        );
+
+        let report = scan_mobile_path(&root).expect("scan should succeed");
+        let found: Vec<_> = report.findings.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(found, ["mobile.android.cleartext-traffic-enabled"]);
+        assert_eq!(report.suppressed.len(), 1);
+        assert_eq!(
+            report.suppressed[0]
+                .suppression
+                .as_ref()
+                .map(|suppression| suppression.reason.as_str()),
+            Some("flavor interno de QA")
+        );
+        assert!(report.warnings.is_empty());
+
+        fs::remove_dir_all(root).ok();
+    }
+
+    #[test]
+    fn skips_checks_disabled_by_rule_or_check_id() {
+        let root = unique_temp_dir("panthereyes-mobile-disabled");
+        write_file(
+            &root.join("AndroidManifest.xml"),
+            r#"<application android:debuggable="true" android:usesCleartextTraffic="true"></application>"#,
+        );
+
+        let request = ScanRequest::new(TargetKind::Mobile, &root).with_disabled_checks([
+            "mobile.debug.disabled",
+            "mobile.android.cleartext-traffic-enabled",
+        ]);
+        let report = MobileChecksEngine::default()
+            .scan(&request)
+            .expect("scan should succeed");
+        assert!(report.findings.is_empty());
+        assert_eq!(report.status, panthereyes_core::ScanStatus::Pass);
+
+        fs::remove_dir_all(root).ok();
+    }
+
+    struct AllowBackupCheck;
+
+    impl AllowBackupCheck {
+        const METADATA: CheckMetadata = CheckMetadata {
+            id: "custom.android.allow-backup",
+            rule_id: "custom.android.allow-backup",
+            title: "Backup habilitado",
+            description: "AndroidManifest.xml nao deve habilitar android:allowBackup.",
+            target: TargetKind::Android,
+            default_severity: Severity::Low,
+            tags: &["custom"],
+            allow_exception: true,
+        };
+    }
+
+    impl Check for AllowBackupCheck {
+        fn metadata(&self) -> &CheckMetadata {
+            &Self::METADATA
+        }
+
+        fn run(&self, ctx: &CheckContext<'_>, report: &mut ScanReport) -> Result<(), EngineError> {
+            for file in ctx.find_by_name("AndroidManifest.xml") {
+                let Some(raw) = ctx.read_file(file)? else {
+                    continue;
+                };
+                if raw.contains(r#"android:allowBackup="true""#) {
+                    report.push(
+                        Finding::for_check(&Self::METADATA, "Backup habilitado.", "Desabilite.")
+                            .with_file(&file.relative),
+                    );
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn builder_adds_custom_checks_and_drops_builtin_ones() {
+        let root = unique_temp_dir("panthereyes-mobile-builder");
+        write_file(
+            &root.join("AndroidManifest.xml"),
+            r#"<application android:debuggable="true" android:allowBackup="true"></application>"#,
+        );
+
+        let registry = CheckRegistry::new().with_check(AllowBackupCheck);
+        let engine = MobileChecksEngine::default()
+            .without_check("mobile.debug.disabled")
+            .with_registry(&registry);
+        let ids: Vec<_> = engine.checks().iter().map(|check| check.id).collect();
+        assert_eq!(
+            ids,
+            [
+                "mobile.ios.ats.arbitrary-loads-enabled",
+                "mobile.android.cleartext-traffic-enabled",
+                "custom.android.allow-backup",
+            ]
+        );
+
+        let request = ScanRequest::new(TargetKind::Mobile, &root);
+        let report = engine.scan(&request).expect("scan should succeed");
+        let found: Vec<_> = report.findings.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(found, ["custom.android.allow-backup"]);
+
+        let empty = MobileChecksEngine::empty().scan(&request).expect("scan");
+        assert!(empty.findings.is_empty());
+
+        fs::remove_dir_all(root).ok();
+    }
 }
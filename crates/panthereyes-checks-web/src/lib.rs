@@ -1,23 +1,49 @@
-use std::fs;
-use std::path::{Path, PathBuf};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
 
 use panthereyes_core::{
-    EngineError, Finding, ScanEngine, ScanReport, ScanRequest, Severity, TargetKind,
+    run_workspace_checks, Check, CheckContext, CheckDiagnostic, CheckMetadata, CheckRegistry,
+    EngineError, Finding, ScanEngine, ScanReport, ScanRequest, Severity, TargetKind, Workspace,
+    WorkspaceFile,
 };
 
+#[derive(Clone)]
 pub struct WebChecksEngine {
-    checks: Vec<Box<dyn WebCheck>>,
+    checks: Vec<Arc<dyn Check>>,
 }
 
 impl Default for WebChecksEngine {
     fn default() -> Self {
-        Self {
-            checks: vec![
-                Box::new(NginxCspHeaderCheck),
-                Box::new(NginxHstsHeaderCheck),
-                Box::new(PreparedPlaceholderCheck),
-            ],
-        }
+        // Room for future checks (framework-specific config, IaC, CDN/WAF, etc.)
+        Self::empty()
+            .with_check(NginxCspHeaderCheck)
+            .with_check(NginxHstsHeaderCheck)
+    }
+}
+
+impl WebChecksEngine {
+    /// Engine without any check, to assemble a custom check list.
+    pub fn empty() -> Self {
+        Self { checks: Vec::new() }
+    }
+
+    pub fn with_check(mut self, check: impl Check + 'static) -> Self {
+        self.checks.push(Arc::new(check));
+        self
+    }
+
+    /// Adds the registry checks that report web findings.
+    pub fn with_registry(mut self, registry: &CheckRegistry) -> Self {
+        self.checks
+            .extend(registry.checks_for(TargetKind::Web).cloned());
+        self
+    }
+
+    /// Drops the checks matching a finding id or catalog rule id.
+    pub fn without_check(mut self, id: &str) -> Self {
+        self.checks.retain(|check| !check.metadata().implements(id));
+        self
     }
 }
 
@@ -26,11 +52,23 @@ impl ScanEngine for WebChecksEngine {
         "panthereyes-checks-web"
     }
 
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
     fn target(&self) -> TargetKind {
         TargetKind::Web
     }
 
     fn scan(&self, request: &ScanRequest) -> Result<ScanReport, EngineError> {
+        self.scan_workspace(request, &request.discover_workspace()?)
+    }
+
+    fn scan_workspace(
+        &self,
+        request: &ScanRequest,
+        workspace: &Workspace,
+    ) -> Result<ScanReport, EngineError> {
         if request.target != TargetKind::Web {
             return Err(EngineError::InvalidRequest(format!(
                 "web engine only supports target '{}', got '{}'",
@@ -39,15 +77,20 @@ impl ScanEngine for WebChecksEngine {
             )));
         }
 
-        let mut report = ScanReport::new(TargetKind::Web);
-        let ctx = WebScanContext::new(request.path.clone())?;
+        run_workspace_checks(TargetKind::Web, &self.checks, request, workspace)
+    }
 
-        for check in &self.checks {
-            check.run(&ctx, &mut report)?;
-        }
+    fn checks(&self) -> Vec<CheckMetadata> {
+        self.checks.iter().map(|check| *check.metadata()).collect()
+    }
 
-        report.recompute_status();
-        Ok(report)
+    fn applies_to(&self, workspace: &Workspace) -> bool {
+        workspace.files().iter().any(|file| {
+            file.file_name().is_some_and(|name| {
+                let lower = name.to_ascii_lowercase();
+                is_web_config_name(&lower) || lower == "package.json" || lower == "index.html"
+            })
+        })
     }
 }
 
@@ -60,110 +103,68 @@ pub fn scan_web_path(path: impl AsRef<Path>) -> Result<ScanReport, EngineError>
 pub fn run_demo_web_checks(path: &str) -> ScanReport {
     scan_web_path(path).unwrap_or_else(|err| {
         let mut report = ScanReport::new(TargetKind::Web);
-        report.push(Finding::new(
-            "web.engine.error",
-            "Falha ao executar checks web",
-            Severity::High,
-            TargetKind::Web,
-            err.to_string(),
-            "Revise o caminho informado e o acesso aos arquivos de configuracao web.",
-        ));
+        report.push_diagnostic(CheckDiagnostic::from_error("panthereyes-checks-web", &err));
         report
     })
 }
 
-trait WebCheck {
-    fn run(&self, ctx: &WebScanContext, report: &mut ScanReport) -> Result<(), EngineError>;
+fn candidate_config_files<'a>(ctx: &CheckContext<'a>) -> impl Iterator<Item = &'a WorkspaceFile> {
+    ctx.files().iter().filter(|file| {
+        file.file_name()
+            .is_some_and(|name| is_web_config_name(&name.to_ascii_lowercase()))
+    })
 }
 
-struct WebScanContext {
-    root: PathBuf,
-    files: Vec<PathBuf>,
+/// Server and hosting config names, lowercased (nginx, Caddy, Vercel, Netlify).
+fn is_web_config_name(lower: &str) -> bool {
+    lower == "nginx.conf"
+        || lower.ends_with(".conf")
+        || lower == "caddyfile"
+        || lower == "vercel.json"
+        || lower == "netlify.toml"
 }
 
-impl WebScanContext {
-    fn new(root: PathBuf) -> Result<Self, EngineError> {
-        if !root.exists() {
-            return Err(EngineError::InvalidRequest(format!(
-                "scan path not found: {}",
-                root.display()
-            )));
-        }
-
-        let mut files = Vec::new();
-        collect_files(&root, &mut files)?;
-        Ok(Self { root, files })
-    }
-
-    fn candidate_config_files(&self) -> Vec<&PathBuf> {
-        self.files
-            .iter()
-            .filter(|path| {
-                let Some(name) = path.file_name().and_then(|v| v.to_str()) else {
-                    return false;
-                };
-                let lower = name.to_ascii_lowercase();
-                lower == "nginx.conf"
-                    || lower.ends_with(".conf")
-                    || lower == "caddyfile"
-                    || lower == "vercel.json"
-                    || lower == "netlify.toml"
-            })
-            .collect()
-    }
-
-    fn read_to_string(&self, path: &Path) -> Result<String, EngineError> {
-        fs::read_to_string(path).map_err(|source| EngineError::io(path.to_path_buf(), source))
-    }
-
-    fn relative_path(&self, path: &Path) -> PathBuf {
-        path.strip_prefix(&self.root)
-            .map_or_else(|_| path.to_path_buf(), Path::to_path_buf)
-    }
+pub struct NginxCspHeaderCheck;
+
+impl NginxCspHeaderCheck {
+    const METADATA: CheckMetadata = CheckMetadata {
+        id: "web.headers.csp.missing",
+        rule_id: "web.csp.required",
+        title: "Content-Security-Policy ausente",
+        description: "Configuracoes Nginx devem definir o header Content-Security-Policy.",
+        target: TargetKind::Web,
+        default_severity: Severity::High,
+        tags: &["web", "headers", "xss"],
+        allow_exception: true,
+    };
 }
 
-fn collect_files(root: &Path, out: &mut Vec<PathBuf>) -> Result<(), EngineError> {
-    if root.is_file() {
-        out.push(root.to_path_buf());
-        return Ok(());
-    }
-
-    let entries =
-        fs::read_dir(root).map_err(|source| EngineError::io(root.to_path_buf(), source))?;
-    for entry in entries {
-        let entry = entry.map_err(|source| EngineError::io(root.to_path_buf(), source))?;
-        let path = entry.path();
-        let file_type = entry
-            .file_type()
-            .map_err(|source| EngineError::io(path.clone(), source))?;
-
-        if file_type.is_dir() {
-            collect_files(&path, out)?;
-        } else if file_type.is_file() {
-            out.push(path);
-        }
+impl Check for NginxCspHeaderCheck {
+    fn metadata(&self) -> &CheckMetadata {
+        &Self::METADATA
     }
 
-    Ok(())
-}
-
-struct NginxCspHeaderCheck;
+    fn is_file_local(&self) -> bool {
+        true
+    }
 
-impl WebCheck for NginxCspHeaderCheck {
-    fn run(&self, ctx: &WebScanContext, report: &mut ScanReport) -> Result<(), EngineError> {
-        for config in ctx.candidate_config_files() {
-            let raw = ctx.read_to_string(config)?;
-            if looks_like_nginx_config(&raw) && !raw.contains("Content-Security-Policy") {
+    fn run(&self, ctx: &CheckContext<'_>, report: &mut ScanReport) -> Result<(), EngineError> {
+        for config in candidate_config_files(ctx) {
+            let Some(raw) = ctx.read_file(config)? else {
+                continue;
+            };
+            let Some(block) = find_nginx_block(&raw) else {
+                continue;
+            };
+            if !raw.contains("Content-Security-Policy") {
                 report.push(
-                    Finding::new(
-                        "web.headers.csp.missing",
-                        "Content-Security-Policy ausente",
-                        Severity::High,
-                        TargetKind::Web,
+                    Finding::for_check(
+                        &Self::METADATA,
                         "Arquivo de configuracao web aparenta nao definir header Content-Security-Policy.",
                         "Adicione um header CSP apropriado (ex.: via add_header no Nginx) e ajuste por app/rota.",
                     )
-                    .with_file(ctx.relative_path(config)),
+                    .with_file(&config.relative)
+                    .with_match(&raw, block),
                 );
             }
         }
@@ -171,26 +172,50 @@ impl WebCheck for NginxCspHeaderCheck {
     }
 }
 
-struct NginxHstsHeaderCheck;
+pub struct NginxHstsHeaderCheck;
+
+impl NginxHstsHeaderCheck {
+    const METADATA: CheckMetadata = CheckMetadata {
+        id: "web.headers.hsts.missing",
+        rule_id: "web.hsts.required",
+        title: "HSTS ausente em listener HTTPS",
+        description: "Listeners HTTPS devem enviar o header Strict-Transport-Security.",
+        target: TargetKind::Web,
+        default_severity: Severity::Medium,
+        tags: &["web", "headers", "transport"],
+        allow_exception: true,
+    };
+}
+
+impl Check for NginxHstsHeaderCheck {
+    fn metadata(&self) -> &CheckMetadata {
+        &Self::METADATA
+    }
 
-impl WebCheck for NginxHstsHeaderCheck {
-    fn run(&self, ctx: &WebScanContext, report: &mut ScanReport) -> Result<(), EngineError> {
-        for config in ctx.candidate_config_files() {
-            let raw = ctx.read_to_string(config)?;
-            if looks_like_nginx_config(&raw)
-                && raw.contains("listen 443")
-                && !raw.contains("Strict-Transport-Security")
-            {
+    fn is_file_local(&self) -> bool {
+        true
+    }
+
+    fn run(&self, ctx: &CheckContext<'_>, report: &mut ScanReport) -> Result<(), EngineError> {
+        for config in candidate_config_files(ctx) {
+            let Some(raw) = ctx.read_file(config)? else {
+                continue;
+            };
+            if find_nginx_block(&raw).is_none() {
+                continue;
+            }
+            let Some(listener) = find_https_listener(&raw) else {
+                continue;
+            };
+            if !raw.contains("Strict-Transport-Security") {
                 report.push(
-                    Finding::new(
-                        "web.headers.hsts.missing",
-                        "HSTS ausente em listener HTTPS",
-                        Severity::Medium,
-                        TargetKind::Web,
+                    Finding::for_check(
+                        &Self::METADATA,
                         "Configuracao com listener HTTPS encontrada sem header Strict-Transport-Security.",
                         "Configure HSTS (Strict-Transport-Security) com max-age adequado e includeSubDomains quando aplicavel.",
                     )
-                    .with_file(ctx.relative_path(config)),
+                    .with_file(&config.relative)
+                    .with_match(&raw, listener),
                 );
             }
         }
@@ -198,15 +223,15 @@ impl WebCheck for NginxHstsHeaderCheck {
     }
 }
 
-struct PreparedPlaceholderCheck;
-
-impl WebCheck for PreparedPlaceholderCheck {
-    fn run(&self, _ctx: &WebScanContext, _report: &mut ScanReport) -> Result<(), EngineError> {
-        // Reserved for future checks (framework-specific config, IaC, CDN/WAF, etc.)
-        Ok(())
-    }
+/// Locates the first nginx block marker; absence means the file is not an nginx config.
+fn find_nginx_block(raw: &str) -> Option<Range<usize>> {
+    ["server {", "http {", "location /"]
+        .iter()
+        .filter_map(|marker| raw.find(marker).map(|start| start..start + marker.len()))
+        .min_by_key(|range| range.start)
 }
 
-fn looks_like_nginx_config(raw: &str) -> bool {
-    raw.contains("server {") || raw.contains("http {") || raw.contains("location /")
+fn find_https_listener(raw: &str) -> Option<Range<usize>> {
+    let marker = "listen 443";
+    raw.find(marker).map(|start| start..start + marker.len())
 }
@@ -0,0 +1,184 @@
+use std::path::{Path, PathBuf};
+
+use panthereyes_core::{Finding, Severity};
+use serde::Serialize;
+
+use crate::{config_root, repository_path, ScanCommandOutput};
+
+/// `scan --format gitlab-codequality`: the Code Climate issues array GitLab
+/// reads from a `codequality` report artifact, one issue per finding.
+/// GitLab anchors issues to a file, so findings without one (e.g. about the
+/// whole target) are left out, as are inline-suppressed findings; excepted
+/// ones are kept as `info`.
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+pub(crate) struct CodeQualityReport<'a> {
+    issues: Vec<Issue<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct Issue<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    check_name: &'a str,
+    description: String,
+    content: Content<'a>,
+    categories: [&'static str; 1],
+    severity: &'static str,
+    location: Location,
+    fingerprint: String,
+}
+
+#[derive(Debug, Serialize)]
+struct Content<'a> {
+    body: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct Location {
+    path: String,
+    lines: Lines,
+}
+
+#[derive(Debug, Serialize)]
+struct Lines {
+    begin: usize,
+    end: usize,
+}
+
+impl<'a> CodeQualityReport<'a> {
+    pub(crate) fn from_scan(output: &'a ScanCommandOutput) -> Self {
+        // GitLab resolves paths from the project checkout, the job's working
+        // directory unless the scan path is absolute.
+        let root = std::env::var_os("CI_PROJECT_DIR").map(PathBuf::from);
+        Self::with_root(output, root.as_deref())
+    }
+
+    fn with_root(output: &'a ScanCommandOutput, root: Option<&Path>) -> Self {
+        let base = config_root(&output.path);
+        let issues = output
+            .summary
+            .findings()
+            .iter()
+            .filter_map(|finding| issue_for(finding, root, base))
+            .collect();
+        Self { issues }
+    }
+}
+
+fn issue_for<'a>(finding: &'a Finding, root: Option<&Path>, base: &Path) -> Option<Issue<'a>> {
+    let file = finding.file.as_deref()?;
+    // Outside a GitLab checkout, fall back to the path from the scan root.
+    let path = repository_path(root, base, file)
+        .unwrap_or_else(|| file.to_string_lossy().replace('\\', "/"));
+    let lines = finding
+        .span
+        .map_or(Lines { begin: 1, end: 1 }, |span| Lines {
+            begin: span.line,
+            end: span.end_line.max(span.line),
+        });
+    Some(Issue {
+        kind: "issue",
+        check_name: &finding.id,
+        description: format!("{}: {}", finding.title, finding.message),
+        content: Content {
+            body: &finding.remediation,
+        },
+        categories: ["Security"],
+        severity: match &finding.exception {
+            Some(_) => "info",
+            None => severity(finding.severity),
+        },
+        location: Location { path, lines },
+        fingerprint: finding
+            .fingerprint
+            .clone()
+            .unwrap_or_else(|| finding.compute_fingerprint()),
+    })
+}
+
+fn severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "blocker",
+        Severity::High => "critical",
+        Severity::Medium => "major",
+        Severity::Low => "minor",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scan_output;
+    use panthereyes_core::{InlineSuppression, ScanReport, SourceSpan, TargetKind};
+
+    #[test]
+    fn maps_findings_to_code_climate_issues() {
+        let finding = |id: &str, severity| {
+            Finding::new(
+                id,
+                "Aplicacao Android debuggable",
+                severity,
+                TargetKind::Android,
+                "android:debuggable=true detectado.",
+                "Desabilite debuggable em builds release.",
+            )
+            .with_file("app/src/main/AndroidManifest.xml")
+        };
+        let mut report = ScanReport::new(TargetKind::Mobile);
+        report.push(
+            finding("mobile.android.debuggable-enabled", Severity::Critical).with_span(
+                SourceSpan {
+                    line: 5,
+                    column: 9,
+                    end_line: 6,
+                    end_column: 4,
+                },
+            ),
+        );
+        let mut excepted = finding("mobile.android.cleartext-traffic-enabled", Severity::High);
+        excepted.exception = Some("EX-1".to_string());
+        report.push(excepted);
+        let mut suppressed = finding("mobile.android.backup-enabled", Severity::Low);
+        suppressed.suppression = Some(InlineSuppression {
+            line: 1,
+            rules: vec!["mobile.android.backup-enabled".to_string()],
+            reason: "backup cifrado".to_string(),
+        });
+        report.suppressed.push(suppressed);
+        report.push(Finding::new(
+            "mobile.release.signing",
+            "Assinatura de release ausente",
+            Severity::Medium,
+            TargetKind::Android,
+            "Nenhuma configuracao de assinatura encontrada.",
+            "Configure a assinatura de release.",
+        ));
+        let mut output = scan_output(report, Vec::new());
+        output.path = PathBuf::from("./android");
+
+        let value = serde_json::to_value(CodeQualityReport::with_root(&output, None)).unwrap();
+        let issues = value.as_array().expect("issues array");
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0]["type"], "issue");
+        assert_eq!(issues[0]["check_name"], "mobile.android.debuggable-enabled");
+        assert_eq!(
+            issues[0]["description"],
+            "Aplicacao Android debuggable: android:debuggable=true detectado."
+        );
+        assert_eq!(issues[0]["severity"], "blocker");
+        assert_eq!(
+            issues[0]["location"],
+            serde_json::json!({
+                "path": "android/app/src/main/AndroidManifest.xml",
+                "lines": { "begin": 5, "end": 6 }
+            })
+        );
+        assert_eq!(
+            issues[0]["fingerprint"],
+            output.summary.findings()[0].fingerprint.clone().unwrap()
+        );
+        assert_eq!(issues[1]["severity"], "info");
+        assert_eq!(issues[1]["location"]["lines"]["begin"], 1);
+    }
+}
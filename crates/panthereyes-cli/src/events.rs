@@ -0,0 +1,134 @@
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::{Context, Result};
+use panthereyes_core::{
+    CheckMetadata, CheckRun, Finding, ScanObserver, ScanReport, TargetKind, WorkspaceFile,
+};
+use serde::Serialize;
+
+use crate::ScanCommandOutput;
+
+/// One line of `scan --format jsonl`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum ScanEvent<'a> {
+    ScanStarted {
+        target: TargetKind,
+        files: usize,
+        checks: usize,
+    },
+    CheckStarted {
+        check: &'a str,
+        rule_id: &'a str,
+    },
+    FileVisited {
+        check: &'a str,
+        path: &'a Path,
+    },
+    Finding(&'a Finding),
+    CheckFinished(&'a CheckRun),
+    ScanFinished {
+        target: TargetKind,
+        findings: usize,
+    },
+    /// Last line: the same document as `--json`, gated by policy and baseline.
+    Summary(&'a ScanCommandOutput),
+}
+
+impl ScanEvent<'_> {
+    pub(crate) fn emit(&self) -> Result<()> {
+        let line = serde_json::to_string(self).context("failed to serialize scan event")?;
+        println!("{line}");
+        Ok(())
+    }
+}
+
+/// Streams scan events as JSON Lines on stdout and/or progress lines on stderr.
+#[derive(Debug, Default)]
+pub(crate) struct ScanEvents {
+    stream: bool,
+    progress: bool,
+    checks: AtomicUsize,
+    finished: AtomicUsize,
+}
+
+impl ScanEvents {
+    pub(crate) fn new(stream: bool, progress: bool) -> Self {
+        Self {
+            stream,
+            progress,
+            ..Self::default()
+        }
+    }
+
+    /// Observer callbacks cannot fail; an event that does not serialize is
+    /// dropped and the summary line still carries its data.
+    fn stream(&self, event: ScanEvent<'_>) {
+        if self.stream {
+            let _ = event.emit();
+        }
+    }
+}
+
+impl ScanObserver for ScanEvents {
+    fn scan_started(&self, target: TargetKind, files: usize, checks: usize) {
+        self.checks.store(checks, Ordering::Relaxed);
+        self.finished.store(0, Ordering::Relaxed);
+        if self.progress {
+            eprintln!("scan {}: {checks} checks, {files} files", target.as_str());
+        }
+        self.stream(ScanEvent::ScanStarted {
+            target,
+            files,
+            checks,
+        });
+    }
+
+    fn check_started(&self, check: &CheckMetadata) {
+        self.stream(ScanEvent::CheckStarted {
+            check: check.id,
+            rule_id: check.rule_id,
+        });
+    }
+
+    fn file_visited(&self, check: &CheckMetadata, file: &WorkspaceFile) {
+        self.stream(ScanEvent::FileVisited {
+            check: check.id,
+            path: &file.relative,
+        });
+    }
+
+    fn finding_emitted(&self, finding: &Finding) {
+        self.stream(ScanEvent::Finding(finding));
+    }
+
+    fn check_finished(&self, run: &CheckRun) {
+        let finished = self.finished.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.progress {
+            eprintln!(
+                "[{finished}/{}] {}: {} in {:.1} ms, {} files read",
+                self.checks.load(Ordering::Relaxed),
+                run.id,
+                run.status.as_str(),
+                run.duration_us as f64 / 1_000.0,
+                run.files_considered
+            );
+        }
+        self.stream(ScanEvent::CheckFinished(run));
+    }
+
+    fn scan_finished(&self, report: &ScanReport) {
+        if self.progress {
+            eprintln!(
+                "scan {}: {} findings",
+                report.target.as_str(),
+                report.findings.len()
+            );
+        }
+        self.stream(ScanEvent::ScanFinished {
+            target: report.target,
+            findings: report.findings.len(),
+        });
+    }
+}
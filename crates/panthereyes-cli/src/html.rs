@@ -0,0 +1,512 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use panthereyes_core::{
+    CheckDiagnostic, Finding, PolicyResult, ScanMetadata, ScanStatus, ScanWarning, Severity,
+    TargetKind,
+};
+use panthereyes_policy::EffectivePolicy;
+
+use crate::{escape_markup as escape, ScanCommandOutput, ScanSummary};
+
+const SEVERITIES: [Severity; 4] = [
+    Severity::Critical,
+    Severity::High,
+    Severity::Medium,
+    Severity::Low,
+];
+
+const STYLE: &str = r#"
+:root { --pass: #1a7f37; --warn: #9a6700; --block: #cf222e; --muted: #57606a; --line: #d0d7de; }
+* { box-sizing: border-box; }
+body { margin: 0 auto; max-width: 72rem; padding: 1.5rem; font: 15px/1.5 system-ui, -apple-system, "Segoe UI", sans-serif; color: #1f2328; }
+h1 { margin: 0 0 .5rem; font-size: 1.6rem; }
+h2 { margin-top: 2rem; border-bottom: 1px solid var(--line); padding-bottom: .25rem; }
+dl.context { display: grid; grid-template-columns: max-content 1fr; gap: .1rem 1rem; margin: 0; color: var(--muted); }
+dl.context dt { font-weight: 600; }
+dl.context dd { margin: 0; }
+table { border-collapse: collapse; margin: .5rem 0; }
+th, td { border: 1px solid var(--line); padding: .25rem .6rem; text-align: left; vertical-align: top; }
+code, pre { font: 13px/1.4 ui-monospace, SFMono-Regular, Menlo, monospace; }
+pre { white-space: pre-wrap; background: #f6f8fa; padding: .5rem; border-radius: 4px; margin: .25rem 0; }
+.status { display: inline-block; padding: .1rem .6rem; border-radius: 1rem; color: #fff; font-weight: 600; text-transform: uppercase; }
+.status-pass { background: var(--pass); } .status-warn { background: var(--warn); } .status-block { background: var(--block); }
+.badge { display: inline-block; min-width: 4.5rem; text-align: center; padding: 0 .4rem; border-radius: 3px; color: #fff; font-size: 12px; font-weight: 600; text-transform: uppercase; }
+.sev-critical { background: #8c1d18; } .sev-high { background: var(--block); } .sev-medium { background: var(--warn); } .sev-low { background: var(--muted); }
+.notice { border-left: 4px solid var(--warn); background: #fff8c5; padding: .5rem 1rem; }
+#filters { position: sticky; top: 0; background: #fff; padding: .5rem 0; border-bottom: 1px solid var(--line); display: flex; flex-wrap: wrap; gap: .75rem; align-items: center; }
+#filters input[type=search] { flex: 1; min-width: 12rem; padding: .25rem .5rem; }
+details.file { margin: .75rem 0; border: 1px solid var(--line); border-radius: 6px; }
+details.file > summary { padding: .4rem .75rem; background: #f6f8fa; cursor: pointer; }
+.finding { padding: .5rem .75rem; border-top: 1px solid var(--line); }
+.finding.non-gating { opacity: .75; }
+.finding header { display: flex; gap: .5rem; align-items: baseline; flex-wrap: wrap; }
+.finding p { margin: .25rem 0; }
+.meta { color: var(--muted); font-size: 13px; margin: .25rem 0 0; padding-left: 1.2rem; }
+.empty { color: var(--muted); }
+"#;
+
+const SCRIPT: &str = r#"
+(function () {
+  var form = document.getElementById('filters');
+  function apply() {
+    var shown = {};
+    form.querySelectorAll('input[name=severity]').forEach(function (box) { shown[box.value] = box.checked; });
+    var gatingOnly = form.elements.gating.checked;
+    var query = form.elements.query.value.trim().toLowerCase();
+    document.querySelectorAll('.finding').forEach(function (finding) {
+      finding.hidden = !(shown[finding.dataset.severity]
+        && (!gatingOnly || finding.dataset.gating === 'true')
+        && (!query || finding.textContent.toLowerCase().indexOf(query) !== -1));
+    });
+    document.querySelectorAll('details.file').forEach(function (file) {
+      file.hidden = !file.querySelector('.finding:not([hidden])');
+    });
+  }
+  form.addEventListener('input', apply);
+  form.addEventListener('submit', function (event) { event.preventDefault(); });
+})();
+"#;
+
+/// `scan --format html`: a single offline page (inline style and script, no
+/// external assets) with the status, severity counts, findings grouped by
+/// target and file with a client-side filter, and the policy they were
+/// gated with.
+pub(crate) fn render(output: &ScanCommandOutput) -> String {
+    let summary = &output.summary;
+    let (status, score, incomplete, unfinished, warnings, diagnostics, metadata) = match summary {
+        ScanSummary::Single(report) => (
+            report.status,
+            report.score,
+            report.incomplete,
+            &report.unfinished_checks,
+            &report.warnings,
+            &report.diagnostics,
+            report.metadata.as_ref(),
+        ),
+        ScanSummary::Combined(report) => (
+            report.status,
+            report.score,
+            report.incomplete,
+            &report.unfinished_checks,
+            &report.warnings,
+            &report.diagnostics,
+            report.metadata.as_ref(),
+        ),
+    };
+    let targets: Vec<(TargetKind, ScanStatus, u8, &PolicyResult)> = match summary {
+        ScanSummary::Single(report) => {
+            vec![(report.target, report.status, report.score, &report.policy)]
+        }
+        ScanSummary::Combined(report) => report
+            .targets
+            .iter()
+            .map(|target| (target.target, target.status, target.score, &target.policy))
+            .collect(),
+    };
+
+    let mut html = String::new();
+    let _ = writeln!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>PantherEyes {} - {}</title>\n<style>{STYLE}</style>\n</head>\n<body>",
+        status.as_str(),
+        escape(&output.path.display().to_string())
+    );
+
+    write_header(&mut html, output, metadata);
+    write_summary(&mut html, status, score, summary, &targets);
+    if incomplete {
+        let _ = writeln!(
+            html,
+            "<p class=\"notice\">Incomplete scan: cancelled or timed out, findings may be missing. Unfinished checks: {}.</p>",
+            escape(&unfinished.join(", "))
+        );
+    }
+    write_findings(&mut html, summary.findings(), &targets);
+    write_suppressed(&mut html, summary.suppressed());
+    write_notices(&mut html, warnings, diagnostics);
+    write_policy(&mut html, output, &targets);
+
+    let _ = writeln!(html, "<script>{SCRIPT}</script>\n</body>\n</html>");
+    html
+}
+
+fn write_header(html: &mut String, output: &ScanCommandOutput, metadata: Option<&ScanMetadata>) {
+    html.push_str("<header>\n<h1>PantherEyes scan report</h1>\n<dl class=\"context\">\n");
+    let mut context = vec![
+        ("Path", output.path.display().to_string()),
+        ("Target", output.target.as_str().to_string()),
+        ("Phase", output.phase.as_str().to_string()),
+    ];
+    if let Some(env) = &output.env {
+        context.push(("Env", env.clone()));
+    }
+    if let Some(profile) = &output.profile {
+        context.push(("Profile", profile.clone()));
+    }
+    if let Some(metadata) = metadata {
+        context.push(("Finished", metadata.finished_at.clone()));
+        context.push((
+            "Files",
+            format!(
+                "{} scanned, {} skipped",
+                metadata.files_scanned, metadata.files_skipped
+            ),
+        ));
+        let engines: Vec<_> = metadata
+            .engines
+            .iter()
+            .map(|engine| format!("{} {}", engine.name, engine.version))
+            .collect();
+        if !engines.is_empty() {
+            context.push(("Engines", engines.join(", ")));
+        }
+    }
+    for (term, value) in context {
+        let _ = writeln!(html, "<dt>{term}</dt><dd>{}</dd>", escape(&value));
+    }
+    html.push_str("</dl>\n</header>\n");
+}
+
+fn write_summary(
+    html: &mut String,
+    status: ScanStatus,
+    score: u8,
+    summary: &ScanSummary,
+    targets: &[(TargetKind, ScanStatus, u8, &PolicyResult)],
+) {
+    let findings = summary.findings();
+    let gating = findings
+        .iter()
+        .filter(|finding| finding.counts_toward_status())
+        .count();
+    let _ = writeln!(
+        html,
+        "<h2>Summary</h2>\n<p><span class=\"status status-{0}\">{0}</span> score <strong>{score}/100</strong>, {1} findings ({gating} gating), {2} suppressed</p>",
+        status.as_str(),
+        findings.len(),
+        summary.suppressed().len()
+    );
+
+    html.push_str("<table>\n<tr><th>Severity</th><th>Findings</th><th>Gating</th></tr>\n");
+    for severity in SEVERITIES {
+        let of_severity = || {
+            findings
+                .iter()
+                .filter(move |finding| finding.severity == severity)
+        };
+        let _ = writeln!(
+            html,
+            "<tr><td><span class=\"badge sev-{0}\">{0}</span></td><td>{1}</td><td>{2}</td></tr>",
+            severity.as_str(),
+            of_severity().count(),
+            of_severity()
+                .filter(|finding| finding.counts_toward_status())
+                .count()
+        );
+    }
+    html.push_str("</table>\n");
+
+    if let ScanSummary::Combined(report) = summary {
+        html.push_str(
+            "<table>\n<tr><th>Target</th><th>Status</th><th>Score</th><th>Findings</th></tr>\n",
+        );
+        for ((target, status, score, _), summary) in targets.iter().zip(&report.targets) {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td><span class=\"status status-{1}\">{1}</span>{2}</td><td>{score}/100</td><td>{3}</td></tr>",
+                target.as_str(),
+                status.as_str(),
+                if summary.incomplete { " incomplete" } else { "" },
+                summary.findings
+            );
+        }
+        html.push_str("</table>\n");
+    }
+}
+
+fn write_findings(
+    html: &mut String,
+    findings: &[Finding],
+    targets: &[(TargetKind, ScanStatus, u8, &PolicyResult)],
+) {
+    html.push_str("<h2>Findings</h2>\n<form id=\"filters\">\n");
+    for severity in SEVERITIES {
+        let _ = writeln!(
+            html,
+            "<label><input type=\"checkbox\" name=\"severity\" value=\"{0}\" checked> {0}</label>",
+            severity.as_str()
+        );
+    }
+    html.push_str("<label><input type=\"checkbox\" name=\"gating\"> gating only</label>\n<input type=\"search\" name=\"query\" placeholder=\"Filter by id, file or text\">\n</form>\n");
+
+    for (target, ..) in targets {
+        let mut files: BTreeMap<Option<&Path>, Vec<&Finding>> = BTreeMap::new();
+        for finding in findings
+            .iter()
+            .filter(|finding| finding.target.engine_target() == target.engine_target())
+        {
+            files
+                .entry(finding.file.as_deref())
+                .or_default()
+                .push(finding);
+        }
+        let count: usize = files.values().map(Vec::len).sum();
+        let _ = writeln!(
+            html,
+            "<section class=\"target\">\n<h3>{} ({count})</h3>",
+            target.as_str()
+        );
+        if files.is_empty() {
+            html.push_str("<p class=\"empty\">No findings.</p>\n");
+        }
+        for (file, findings) in files {
+            let file = file.map_or_else(
+                || "(no file)".to_string(),
+                |file| file.display().to_string(),
+            );
+            let _ = writeln!(
+                html,
+                "<details class=\"file\" open>\n<summary><code>{}</code> ({})</summary>",
+                escape(&file),
+                findings.len()
+            );
+            for finding in findings {
+                write_finding(html, finding);
+            }
+            html.push_str("</details>\n");
+        }
+        html.push_str("</section>\n");
+    }
+}
+
+fn write_finding(html: &mut String, finding: &Finding) {
+    let gating = finding.counts_toward_status();
+    let severity = finding.severity.as_str();
+    let _ = writeln!(
+        html,
+        "<article class=\"finding{}\" data-severity=\"{severity}\" data-gating=\"{gating}\">\n<header><span class=\"badge sev-{severity}\">{severity}</span> <strong>{}</strong> <code>{}</code></header>",
+        if gating { "" } else { " non-gating" },
+        escape(&finding.title),
+        escape(&finding.id)
+    );
+    if let Some(location) = finding.location_label() {
+        let _ = writeln!(html, "<p><code>{}</code></p>", escape(&location));
+    }
+    let _ = writeln!(html, "<p>{}</p>", escape(&finding.message));
+    if let Some(evidence) = &finding.evidence {
+        let _ = writeln!(html, "<pre>{}</pre>", escape(evidence));
+    }
+    let _ = writeln!(
+        html,
+        "<p><strong>Remediation:</strong> {}</p>",
+        escape(&finding.remediation)
+    );
+
+    let mut meta = Vec::new();
+    if let Some(rule_id) = &finding.rule_id {
+        meta.push(format!("rule {rule_id}"));
+    }
+    if let Some(original) = finding.original_severity {
+        meta.push(format!(
+            "severity override {} -> {severity}",
+            original.as_str()
+        ));
+    }
+    if let Some(state) = finding.baseline {
+        meta.push(format!("baseline {}", state.as_str()));
+    }
+    if let Some(exception) = &finding.exception {
+        meta.push(format!("exception {exception}"));
+    }
+    if let Some(fingerprint) = &finding.fingerprint {
+        meta.push(format!("fingerprint {fingerprint}"));
+    }
+    if !meta.is_empty() {
+        html.push_str("<ul class=\"meta\">");
+        for item in meta {
+            let _ = write!(html, "<li>{}</li>", escape(&item));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</article>\n");
+}
+
+fn write_suppressed(html: &mut String, suppressed: &[Finding]) {
+    if suppressed.is_empty() {
+        return;
+    }
+    let _ = writeln!(
+        html,
+        "<h2>Suppressed ({})</h2>\n<table>\n<tr><th>Finding</th><th>Location</th><th>Reason</th></tr>",
+        suppressed.len()
+    );
+    for finding in suppressed {
+        let _ = writeln!(
+            html,
+            "<tr><td><span class=\"badge sev-{0}\">{0}</span> <code>{1}</code></td><td><code>{2}</code></td><td>{3}</td></tr>",
+            finding.severity.as_str(),
+            escape(&finding.id),
+            escape(&finding.location_label().unwrap_or_default()),
+            escape(
+                finding
+                    .suppression
+                    .as_ref()
+                    .map_or("", |suppression| suppression.reason.as_str())
+            )
+        );
+    }
+    html.push_str("</table>\n");
+}
+
+fn write_notices(html: &mut String, warnings: &[ScanWarning], diagnostics: &[CheckDiagnostic]) {
+    if !warnings.is_empty() {
+        let _ = writeln!(html, "<h2>Warnings ({})</h2>\n<ul>", warnings.len());
+        for warning in warnings {
+            let _ = writeln!(
+                html,
+                "<li><code>{}</code> {}</li>",
+                escape(&warning.code),
+                escape(&warning.message)
+            );
+        }
+        html.push_str("</ul>\n");
+    }
+    if !diagnostics.is_empty() {
+        let _ = writeln!(html, "<h2>Diagnostics ({})</h2>\n<ul>", diagnostics.len());
+        for diagnostic in diagnostics {
+            let path = diagnostic
+                .path
+                .as_ref()
+                .map(|path| format!(" @ {}", path.display()))
+                .unwrap_or_default();
+            let _ = writeln!(
+                html,
+                "<li><code>{}</code> {}{}: {}</li>",
+                diagnostic.kind.as_str(),
+                escape(&diagnostic.check),
+                escape(&path),
+                escape(&diagnostic.message)
+            );
+        }
+        html.push_str("</ul>\n");
+    }
+}
+
+fn write_policy(
+    html: &mut String,
+    output: &ScanCommandOutput,
+    targets: &[(TargetKind, ScanStatus, u8, &PolicyResult)],
+) {
+    html.push_str("<h2>Policy</h2>\n<table>\n<tr><th>Target</th><th>Mode</th><th>Fail on severity</th><th>Evaluated status</th><th>Min score</th><th>Fail on diagnostics</th></tr>\n");
+    for (target, _, _, policy) in targets {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            target.as_str(),
+            policy.mode.as_str(),
+            policy.blocking_severity.as_str(),
+            policy.status.as_str(),
+            policy
+                .min_score
+                .map_or_else(|| "-".to_string(), |score| score.to_string()),
+            policy.fail_on_diagnostics
+        );
+    }
+    html.push_str("</table>\n");
+    if output.env.is_none() {
+        html.push_str("<p class=\"empty\">No <code>--env</code>: default policy (enforce, fail on high).</p>\n");
+    }
+
+    let policies: Vec<&EffectivePolicy> = output
+        .effective_policy
+        .iter()
+        .chain(&output.effective_policies)
+        .collect();
+    for policy in policies {
+        let _ = writeln!(
+            html,
+            "<h3>{} / {}</h3>",
+            escape(&policy.env),
+            policy.target.as_str()
+        );
+        if !policy.directive_list.is_empty() {
+            html.push_str("<table>\n<tr><th>Directive</th><th>Value</th><th>Set by</th></tr>\n");
+            for directive in &policy.directive_list {
+                let _ = writeln!(
+                    html,
+                    "<tr><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                    escape(&directive.key),
+                    escape(&directive.value.to_string()),
+                    escape(&directive.source)
+                );
+            }
+            html.push_str("</table>\n");
+        }
+        if !policy.rule_overrides.is_empty() {
+            html.push_str(
+                "<table>\n<tr><th>Rule override</th><th>Enabled</th><th>Severity</th></tr>\n",
+            );
+            for (rule_id, rule) in &policy.rule_overrides {
+                let _ = writeln!(
+                    html,
+                    "<tr><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+                    escape(rule_id),
+                    rule.enabled
+                        .map_or_else(|| "-".to_string(), |enabled| enabled.to_string()),
+                    rule.severity.map_or("-", Severity::as_str)
+                );
+            }
+            html.push_str("</table>\n");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scan_output;
+    use panthereyes_core::{InlineSuppression, ScanReport};
+    use std::path::PathBuf;
+
+    #[test]
+    fn renders_an_offline_page_grouped_by_target_and_file() {
+        let mut report = ScanReport::new(TargetKind::Web);
+        let finding = |file: &str, message: &str| {
+            Finding::new(
+                "web.headers.csp.missing",
+                "Content-Security-Policy ausente",
+                Severity::High,
+                TargetKind::Web,
+                message,
+                "Adicione um header CSP.",
+            )
+            .with_file(file)
+        };
+        report.push(finding("b/nginx.conf", "<script>alert(1)</script>"));
+        report.push(finding("a/nginx.conf", "sem CSP"));
+        let mut suppressed = finding("c/nginx.conf", "sem CSP");
+        suppressed.suppression = Some(InlineSuppression {
+            line: 1,
+            rules: vec!["web.csp.required".to_string()],
+            reason: "servido pelo CDN".to_string(),
+        });
+        report.suppressed.push(suppressed);
+        let mut output = scan_output(report, Vec::new());
+        output.path = PathBuf::from("site");
+
+        let page = render(&output);
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.trim_end().ends_with("</html>"));
+        assert!(!page.contains("src=\"http") && !page.contains("href=\"http"));
+        assert!(page.contains("<span class=\"status status-block\">block</span>"));
+        assert!(page.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+        let first = page.find("<code>a/nginx.conf</code>").expect("a group");
+        let second = page.find("<code>b/nginx.conf</code>").expect("b group");
+        assert!(first < second, "files are grouped in path order");
+        assert!(page.contains("<h2>Suppressed (1)</h2>"));
+        assert!(page.contains("servido pelo CDN"));
+        assert!(page.contains("data-severity=\"high\" data-gating=\"true\""));
+    }
+}
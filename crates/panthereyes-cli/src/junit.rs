@@ -0,0 +1,345 @@
+use std::fmt::Write as _;
+
+use panthereyes_core::{
+    CheckDiagnostic, CheckRun, CheckRunStatus, Finding, ScanMetadata, ScanStatus, TargetKind,
+};
+
+use crate::{escape_markup, ScanCommandOutput, ScanSummary};
+
+/// `scan --format junit`: one `<testsuite>` per scanned target and one
+/// `<testcase>` per check. A check fails when it reported a finding that
+/// gates the scan, errors when it failed or panicked, and is skipped when it
+/// was disabled or did not finish.
+pub(crate) fn render(output: &ScanCommandOutput) -> String {
+    let summary = &output.summary;
+    let suites: Vec<(TargetKind, ScanStatus, u8)> = match summary {
+        ScanSummary::Single(report) => vec![(report.target, report.status, report.score)],
+        ScanSummary::Combined(report) => report
+            .targets
+            .iter()
+            .map(|target| (target.target, target.status, target.score))
+            .collect(),
+    };
+    let (diagnostics, metadata) = match summary {
+        ScanSummary::Single(report) => (&report.diagnostics, report.metadata.as_ref()),
+        ScanSummary::Combined(report) => (&report.diagnostics, report.metadata.as_ref()),
+    };
+
+    let suites: Vec<Suite<'_>> = suites
+        .into_iter()
+        .map(|(target, status, score)| {
+            let target = target.engine_target();
+            let mut cases: Vec<Case<'_>> = output
+                .checks
+                .iter()
+                .filter(|check| check.target.engine_target() == target)
+                .map(|check| Case::new(check.id))
+                .collect();
+            for finding in summary
+                .findings()
+                .iter()
+                .chain(summary.suppressed())
+                .filter(|finding| finding.target.engine_target() == target)
+            {
+                let index = match cases.iter().position(|case| case.id == finding.id) {
+                    Some(index) => index,
+                    None => {
+                        cases.push(Case::new(&finding.id));
+                        cases.len() - 1
+                    }
+                };
+                let case = &mut cases[index];
+                if finding.suppression.is_none() && finding.counts_toward_status() {
+                    case.failures.push(finding);
+                } else {
+                    case.ignored.push(finding);
+                }
+            }
+            for case in &mut cases {
+                case.run = run_of(metadata, case.id);
+                case.diagnostics = diagnostics
+                    .iter()
+                    .filter(|diagnostic| diagnostic.check == case.id)
+                    .collect();
+            }
+            Suite {
+                target,
+                status,
+                score,
+                cases,
+            }
+        })
+        .collect();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"PantherEyes\" {}>",
+        counts(suites.iter().flat_map(|suite| &suite.cases))
+    );
+    for suite in &suites {
+        write_suite(&mut xml, suite, output.env.as_deref(), metadata);
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+struct Suite<'a> {
+    target: TargetKind,
+    status: ScanStatus,
+    score: u8,
+    cases: Vec<Case<'a>>,
+}
+
+struct Case<'a> {
+    id: &'a str,
+    run: Option<&'a CheckRun>,
+    /// Findings that gate the scan.
+    failures: Vec<&'a Finding>,
+    /// Suppressed, excepted or baseline-existing findings.
+    ignored: Vec<&'a Finding>,
+    diagnostics: Vec<&'a CheckDiagnostic>,
+}
+
+impl<'a> Case<'a> {
+    fn new(id: &'a str) -> Self {
+        Self {
+            id,
+            run: None,
+            failures: Vec::new(),
+            ignored: Vec::new(),
+            diagnostics: Vec::new(),
+        }
+    }
+
+    fn outcome(&self) -> Outcome {
+        if !self.diagnostics.is_empty() {
+            return Outcome::Error;
+        }
+        if !self.failures.is_empty() {
+            return Outcome::Failure;
+        }
+        match self.run.map(|run| run.status) {
+            Some(CheckRunStatus::Completed) => Outcome::Passed,
+            Some(CheckRunStatus::Failed) => Outcome::Error,
+            Some(status) => Outcome::Skipped(status.as_str()),
+            // Findings without a recorded run, e.g. from a custom engine.
+            None if !self.ignored.is_empty() => Outcome::Passed,
+            None => Outcome::Skipped("not-run"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Passed,
+    Failure,
+    Error,
+    Skipped(&'static str),
+}
+
+fn run_of<'a>(metadata: Option<&'a ScanMetadata>, id: &str) -> Option<&'a CheckRun> {
+    metadata?.checks.iter().find(|run| run.id == id)
+}
+
+fn counts<'a>(cases: impl Iterator<Item = &'a Case<'a>>) -> String {
+    let (mut tests, mut failures, mut errors, mut skipped, mut micros) = (0, 0, 0, 0, 0);
+    for case in cases {
+        tests += 1;
+        match case.outcome() {
+            Outcome::Passed => {}
+            Outcome::Failure => failures += 1,
+            Outcome::Error => errors += 1,
+            Outcome::Skipped(_) => skipped += 1,
+        }
+        micros += case.run.map_or(0, |run| run.duration_us);
+    }
+    format!(
+        "tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" skipped=\"{skipped}\" time=\"{}\"",
+        seconds(micros)
+    )
+}
+
+fn write_suite(
+    xml: &mut String,
+    suite: &Suite<'_>,
+    env: Option<&str>,
+    metadata: Option<&ScanMetadata>,
+) {
+    let name = format!("panthereyes.{}", suite.target.as_str());
+    let timestamp = metadata
+        .map(|metadata| format!(" timestamp=\"{}\"", escape_markup(&metadata.started_at)))
+        .unwrap_or_default();
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{name}\" {}{timestamp}>",
+        counts(suite.cases.iter())
+    );
+    xml.push_str("    <properties>\n");
+    let mut properties = vec![
+        ("status", suite.status.as_str().to_string()),
+        ("score", suite.score.to_string()),
+    ];
+    if let Some(env) = env {
+        properties.push(("env", env.to_string()));
+    }
+    for (key, value) in properties {
+        let _ = writeln!(
+            xml,
+            "      <property name=\"{key}\" value=\"{}\"/>",
+            escape_markup(&value)
+        );
+    }
+    xml.push_str("    </properties>\n");
+
+    for case in &suite.cases {
+        let time = case
+            .run
+            .map(|run| format!(" time=\"{}\"", seconds(run.duration_us)))
+            .unwrap_or_default();
+        let _ = write!(
+            xml,
+            "    <testcase classname=\"{name}\" name=\"{}\"{time}",
+            escape_markup(case.id)
+        );
+        let outcome = case.outcome();
+        if outcome == Outcome::Passed && case.ignored.is_empty() {
+            xml.push_str("/>\n");
+            continue;
+        }
+        xml.push_str(">\n");
+        match outcome {
+            Outcome::Passed => {}
+            Outcome::Failure => {
+                let first = case.failures[0];
+                let message = match case.failures.len() {
+                    1 => first.message.clone(),
+                    count => format!("{} (+{} more)", first.message, count - 1),
+                };
+                let _ = writeln!(
+                    xml,
+                    "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+                    escape_markup(&message),
+                    first.severity.as_str(),
+                    escape_markup(&findings_text(&case.failures))
+                );
+            }
+            Outcome::Error => {
+                let message = case
+                    .diagnostics
+                    .first()
+                    .map_or("check failed", |diagnostic| diagnostic.message.as_str());
+                let kind = case
+                    .diagnostics
+                    .first()
+                    .map_or("failed", |diagnostic| diagnostic.kind.as_str());
+                let _ = writeln!(
+                    xml,
+                    "      <error message=\"{}\" type=\"{kind}\"/>",
+                    escape_markup(message)
+                );
+            }
+            Outcome::Skipped(reason) => {
+                let _ = writeln!(xml, "      <skipped message=\"{reason}\"/>");
+            }
+        }
+        if !case.ignored.is_empty() {
+            let _ = writeln!(
+                xml,
+                "      <system-out>{}</system-out>",
+                escape_markup(&findings_text(&case.ignored))
+            );
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n");
+}
+
+/// One block per finding: location and message, then its remediation.
+fn findings_text(findings: &[&Finding]) -> String {
+    let mut text = String::new();
+    for finding in findings {
+        let location = finding
+            .location_label()
+            .unwrap_or_else(|| "(no file)".to_string());
+        let _ = writeln!(
+            text,
+            "[{}] {location}: {}",
+            finding.severity.as_str(),
+            finding.message
+        );
+        if let Some(suppression) = &finding.suppression {
+            let _ = writeln!(text, "  suppressed: {}", suppression.reason);
+        }
+        if let Some(exception) = &finding.exception {
+            let _ = writeln!(text, "  exception: {exception}");
+        }
+        if let Some(state) = finding.baseline {
+            let _ = writeln!(text, "  baseline: {}", state.as_str());
+        }
+        let _ = writeln!(text, "  remediation: {}", finding.remediation);
+    }
+    text
+}
+
+fn seconds(micros: u64) -> String {
+    format!("{:.6}", micros as f64 / 1_000_000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scan_output;
+    use panthereyes_checks_web::WebChecksEngine;
+    use panthereyes_core::{DiagnosticKind, ScanEngine, ScanReport, Severity};
+
+    #[test]
+    fn renders_checks_as_testcases_of_their_target_suite() {
+        let checks = WebChecksEngine::default().checks();
+        let mut report = ScanReport::new(TargetKind::Web);
+        report.push(
+            Finding::new(
+                "web.headers.csp.missing",
+                "t",
+                Severity::High,
+                TargetKind::Web,
+                "CSP <ausente> & \"sem\" header",
+                "Adicione CSP.",
+            )
+            .with_file("nginx.conf"),
+        );
+        report.push_diagnostic(CheckDiagnostic {
+            check: "web.headers.hsts.missing".to_string(),
+            kind: DiagnosticKind::Io,
+            path: None,
+            message: "leitura falhou".to_string(),
+        });
+        let mut metadata = ScanMetadata::new(
+            std::time::SystemTime::UNIX_EPOCH,
+            std::time::SystemTime::UNIX_EPOCH,
+        );
+        metadata.checks.push(CheckRun {
+            id: "web.headers.csp.missing".to_string(),
+            rule_id: "web.csp.required".to_string(),
+            status: CheckRunStatus::Completed,
+            duration_us: 1_500,
+            files_considered: 1,
+            files_cached: 0,
+            files_matched: 1,
+        });
+        report.metadata = Some(metadata);
+        let mut output = scan_output(report, checks);
+        output.env = Some("prod".to_string());
+
+        let xml = render(&output);
+        assert!(xml.contains(
+            r#"<testsuite name="panthereyes.web" tests="2" failures="1" errors="1" skipped="0" time="0.001500" timestamp="1970-01-01T00:00:00.000Z">"#
+        ));
+        assert!(xml.contains(r#"<property name="env" value="prod"/>"#));
+        assert!(xml.contains(
+            r#"<failure message="CSP &lt;ausente&gt; &amp; &quot;sem&quot; header" type="high">[high] nginx.conf: CSP"#
+        ));
+        assert!(xml.contains(r#"<error message="leitura falhou" type="io"/>"#));
+        assert!(xml.ends_with("</testsuites>\n"));
+    }
+}
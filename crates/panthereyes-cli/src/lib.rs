@@ -1,13 +1,40 @@
 use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use clap::{Args, Parser, Subcommand, ValueEnum};
-use panthereyes_checks_mobile::scan_mobile_path;
-use panthereyes_checks_web::scan_web_path;
-use panthereyes_core::{ScanReport, ScanStatus, Severity, TargetKind};
+use panthereyes_checks_mobile::MobileChecksEngine;
+use panthereyes_checks_web::WebChecksEngine;
+use panthereyes_core::{
+    Baseline, BaselineSummary, CancellationToken, CheckDiagnostic, CheckMetadata, CheckRegistry,
+    CombinedReport, Finding, ResultCache, ResultCacheFile, ScanOrchestrator, ScanReport,
+    ScanRequest, ScanStatus, ScanWarning, Severity, TargetKind, WorkspaceOptions, BASELINE_VERSION,
+    RESULT_CACHE_DIR,
+};
+use panthereyes_policy::{
+    config_file, parse_exceptions_yaml, parse_policy_yaml, parse_rules_yaml, read_exceptions_file,
+    read_policy_file, read_rules_file, CalendarDate, CatalogValidation, EffectivePolicy,
+    ExceptionMatcher, RulesFile, EXCEPTIONS_FILE_NAME, POLICY_FILE_NAME, RULES_FILE_NAME,
+};
 use serde::Serialize;
+use sha2::{Digest, Sha256};
 use thiserror::Error;
 
+mod codequality;
+mod events;
+mod html;
+mod junit;
+mod markdown;
+mod sarif;
+#[cfg(test)]
+mod test_support;
+
+use codequality::CodeQualityReport;
+use events::{ScanEvent, ScanEvents};
+use sarif::SarifLog;
+
 #[derive(Debug, Parser, Clone, PartialEq, Eq)]
 #[command(
     name = "panthereyes",
@@ -32,6 +59,8 @@ pub enum Commands {
     Policy(PolicyArgs),
     /// Diagnose local environment and CLI readiness
     Doctor(DoctorArgs),
+    /// Baseline related commands
+    Baseline(BaselineArgs),
 }
 
 #[derive(Debug, Args, Clone, PartialEq, Eq)]
@@ -58,6 +87,18 @@ pub enum PolicyCommands {
     Preview(PolicyPreviewArgs),
 }
 
+#[derive(Debug, Args, Clone, PartialEq, Eq)]
+pub struct BaselineArgs {
+    #[command(subcommand)]
+    pub command: BaselineCommands,
+}
+
+#[derive(Debug, Subcommand, Clone, PartialEq, Eq)]
+pub enum BaselineCommands {
+    /// Snapshot current findings so later scans only gate on new ones
+    Create(BaselineCreateArgs),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ScanTarget {
@@ -74,6 +115,51 @@ impl ScanTarget {
     }
 }
 
+/// Targets of `scan`: one engine, every engine (`all`) or the engines whose
+/// project files are found under the scan path (`auto`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetSelection {
+    Web,
+    Mobile,
+    All,
+    Auto,
+}
+
+impl TargetSelection {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Web => "web",
+            Self::Mobile => "mobile",
+            Self::All => "all",
+            Self::Auto => "auto",
+        }
+    }
+
+    fn is_combined(self) -> bool {
+        matches!(self, Self::All | Self::Auto)
+    }
+}
+
+/// How `scan` prints its result. `--json` is a shorthand for `--format json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ScanFormat {
+    Text,
+    Json,
+    /// JSON Lines: one event per line as the scan runs, then the summary.
+    Jsonl,
+    /// SARIF 2.1.0 log for code-scanning dashboards.
+    Sarif,
+    /// JUnit XML, one testsuite per target and one testcase per check.
+    Junit,
+    /// Self-contained HTML report.
+    Html,
+    /// Markdown summary for pull request comments and job summaries.
+    Markdown,
+    /// GitLab Code Quality (Code Climate issues) report.
+    GitlabCodequality,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum ScanPhase {
@@ -92,8 +178,8 @@ impl ScanPhase {
 
 #[derive(Debug, Args, Clone, PartialEq, Eq)]
 pub struct ScanArgs {
-    #[arg(long, value_enum, default_value_t = ScanTarget::Web)]
-    pub target: ScanTarget,
+    #[arg(long, value_enum, default_value_t = TargetSelection::Web)]
+    pub target: TargetSelection,
 
     #[arg(long, value_enum, default_value_t = ScanPhase::Static)]
     pub phase: ScanPhase,
@@ -107,10 +193,121 @@ pub struct ScanArgs {
     #[arg(long, help = "Named profile to load (reserved for future integration)")]
     pub profile: Option<String>,
 
+    #[arg(
+        long,
+        help = "Baseline file; findings already recorded there are reported but do not gate"
+    )]
+    pub baseline: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "Policy environment to gate with (e.g. dev, prod) from .panthereyes/policy.yaml"
+    )]
+    pub env: Option<String>,
+
+    #[arg(
+        long,
+        requires = "env",
+        help = "Policy file to resolve --env from (default: <path>/.panthereyes/policy.yaml)"
+    )]
+    pub policy: Option<PathBuf>,
+
+    #[arg(
+        long,
+        requires = "env",
+        help = "Exceptions file applied for --env (default: <path>/.panthereyes/exceptions.yaml when present)"
+    )]
+    pub exceptions: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub workspace: WorkspaceArgs,
+
+    #[arg(
+        long,
+        short = 'j',
+        value_name = "N",
+        help = "Threads for walking files and running checks (default: available CPUs)"
+    )]
+    pub jobs: Option<usize>,
+
+    #[arg(
+        long,
+        value_enum,
+        help = "Output format (default: text, or json with --json); jsonl streams events as the scan runs, sarif writes a SARIF 2.1.0 log, junit a JUnit XML report, html a self-contained page, markdown a pull request summary, gitlab-codequality a GitLab Code Quality report"
+    )]
+    pub format: Option<ScanFormat>,
+
+    #[arg(
+        long,
+        short = 'o',
+        value_name = "FILE",
+        help = "Write the report to FILE instead of stdout (any format but text and jsonl)"
+    )]
+    pub output: Option<PathBuf>,
+
+    #[arg(long, help = "Print per-check progress on stderr")]
+    pub progress: bool,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Stop the scan after this many seconds and report it as incomplete"
+    )]
+    pub timeout: Option<u64>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Stop any single check after this many seconds"
+    )]
+    pub check_timeout: Option<u64>,
+
+    #[arg(
+        long,
+        help = "Evaluate every file again instead of reusing results cached in <path>/.panthereyes/cache"
+    )]
+    pub no_cache: bool,
+
     #[arg(default_value = ".")]
     pub path: PathBuf,
 }
 
+/// File selection shared by every command that walks a scan path.
+#[derive(Debug, Args, Clone, Default, PartialEq, Eq)]
+pub struct WorkspaceArgs {
+    #[arg(
+        long = "include",
+        value_name = "GLOB",
+        help = "Only scan files matching this glob, relative to the scan path (repeatable)"
+    )]
+    pub include: Vec<String>,
+
+    #[arg(
+        long = "exclude",
+        value_name = "GLOB",
+        help = "Skip files and directories matching this glob (repeatable)"
+    )]
+    pub exclude: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "BYTES",
+        help = "Skip files larger than this many bytes (default: 1048576)"
+    )]
+    pub max_file_size: Option<u64>,
+}
+
+impl WorkspaceArgs {
+    fn options(&self) -> WorkspaceOptions {
+        let defaults = WorkspaceOptions::default();
+        WorkspaceOptions {
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+            max_file_size: self.max_file_size.unwrap_or(defaults.max_file_size),
+        }
+    }
+}
+
 #[derive(Debug, Args, Clone, PartialEq, Eq)]
 pub struct ConfigValidateArgs {
     #[arg(default_value = "panthereyes.toml")]
@@ -132,6 +329,24 @@ pub struct PolicyPreviewArgs {
     pub strict: bool,
 }
 
+#[derive(Debug, Args, Clone, PartialEq, Eq)]
+pub struct BaselineCreateArgs {
+    #[arg(long, value_enum, default_value_t = ScanTarget::Web)]
+    pub target: ScanTarget,
+
+    #[arg(
+        long,
+        help = "Baseline file to write (default: .panthereyes/baseline.json in the scanned directory)"
+    )]
+    pub output: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub workspace: WorkspaceArgs,
+
+    #[arg(default_value = ".")]
+    pub path: PathBuf,
+}
+
 #[derive(Debug, Args, Clone, PartialEq, Eq)]
 pub struct DoctorArgs {
     #[arg(
@@ -152,68 +367,458 @@ pub enum CliError {
 
     #[error("unsupported config extension '{ext}' for {path}")]
     UnsupportedConfigExtension { path: PathBuf, ext: String },
+
+    #[error("baseline file not found: {path}")]
+    BaselineNotFound { path: PathBuf },
+
+    #[error("unsupported baseline version {version} in {path} (expected {expected})")]
+    UnsupportedBaselineVersion {
+        path: PathBuf,
+        version: u32,
+        expected: u32,
+    },
+
+    #[error(
+        "--output cannot be used with --format {format}; use a report format such as json or html"
+    )]
+    OutputFormatNotWritable { format: String },
+}
+
+/// Exit code of `scan` when the gated status is `block`.
+pub const EXIT_BLOCKED: u8 = 1;
+/// Exit code for tool errors: invalid arguments or config, unreadable paths, engine failures.
+pub const EXIT_TOOL_ERROR: u8 = 2;
+
+/// Runs a command and returns the process exit code: `EXIT_BLOCKED` for a
+/// blocked scan, success otherwise. Errors map to `EXIT_TOOL_ERROR` in `main`.
+pub fn run(cli: Cli) -> Result<ExitCode> {
+    run_with_registry(cli, &CheckRegistry::new())
 }
 
-pub fn run(cli: Cli) -> Result<()> {
+/// Same as `run`, with extra checks added to the built-in engines. Custom
+/// `panthereyes` builds pass their own registry here.
+pub fn run_with_registry(cli: Cli, registry: &CheckRegistry) -> Result<ExitCode> {
+    let format = match &cli.command {
+        Commands::Scan(ScanArgs {
+            format: Some(format),
+            ..
+        }) => *format,
+        _ if cli.json => ScanFormat::Json,
+        _ => ScanFormat::Text,
+    };
+    let output_file = match &cli.command {
+        Commands::Scan(args) => args.output.clone(),
+        _ => None,
+    };
+    if output_file.is_some() && matches!(format, ScanFormat::Text | ScanFormat::Jsonl) {
+        return Err(CliError::OutputFormatNotWritable {
+            format: format_name(format),
+        }
+        .into());
+    }
     let output = match cli.command {
-        Commands::Scan(args) => handle_scan(args)?,
+        Commands::Scan(args) => handle_scan(args, registry)?,
         Commands::Config(config) => match config.command {
-            ConfigCommands::Validate(args) => handle_config_validate(args)?,
+            ConfigCommands::Validate(args) => handle_config_validate(args, registry)?,
         },
         Commands::Policy(policy) => match policy.command {
             PolicyCommands::Preview(args) => handle_policy_preview(args)?,
         },
         Commands::Doctor(args) => handle_doctor(args)?,
+        Commands::Baseline(baseline) => match baseline.command {
+            BaselineCommands::Create(args) => handle_baseline_create(args, registry)?,
+        },
     };
 
-    print_output(&output, cli.json)
-}
+    match (render_document(&output, format)?, &output_file) {
+        (Some(document), Some(path)) => {
+            std::fs::write(path, document)
+                .with_context(|| format!("failed to write report {}", path.display()))?;
+            eprintln!("report written to {}", path.display());
+        }
+        (Some(document), None) => print!("{document}"),
+        (None, _) => print_output(&output, format)?,
+    }
 
-trait CheckRunner {
-    fn run_scan(&self, target: ScanTarget, path: &Path) -> Result<ScanReport>;
+    let blocked = matches!(
+        &output,
+        CommandOutput::Scan(scan) if scan.summary.status() == ScanStatus::Block
+    );
+    Ok(if blocked {
+        ExitCode::from(EXIT_BLOCKED)
+    } else {
+        ExitCode::SUCCESS
+    })
 }
 
-struct DemoCheckRunner;
-
-impl CheckRunner for DemoCheckRunner {
-    fn run_scan(&self, target: ScanTarget, path: &Path) -> Result<ScanReport> {
-        match target {
-            ScanTarget::Web => scan_web_path(path).map_err(Into::into),
-            ScanTarget::Mobile => scan_mobile_path(path).map_err(Into::into),
-        }
-    }
+/// Built-in web and mobile engines, each extended with the registry's checks.
+fn build_orchestrator(registry: &CheckRegistry) -> ScanOrchestrator {
+    ScanOrchestrator::new()
+        .with_engine(WebChecksEngine::default().with_registry(registry))
+        .with_engine(MobileChecksEngine::default().with_registry(registry))
 }
 
-fn handle_scan(args: ScanArgs) -> Result<CommandOutput> {
-    let runner = DemoCheckRunner;
-    let path = args.path;
+fn handle_scan(args: ScanArgs, registry: &CheckRegistry) -> Result<CommandOutput> {
+    let orchestrator = build_orchestrator(registry);
+    let path = args.path.clone();
 
     if !path.exists() {
         return Err(CliError::ScanPathNotFound { path }.into());
     }
 
-    let summary = match args.phase {
-        ScanPhase::Static => runner
-            .run_scan(args.target, &path)
-            .with_context(|| format!("failed to run scan checks for {}", path.display()))?,
-        ScanPhase::NonStatic => {
-            let mut report = ScanReport::new(scan_target_kind(args.target));
-            report.recompute_status();
-            report
+    // One walk and content cache shared by every target's engine; the timeout
+    // covers the walk and every target.
+    let cancellation = match args.timeout {
+        Some(seconds) => CancellationToken::with_timeout(Duration::from_secs(seconds)),
+        None => CancellationToken::new(),
+    };
+    let mut base_request = ScanRequest::new(TargetKind::Web, &path)
+        .with_workspace(args.workspace.options())
+        .with_jobs(args.jobs.unwrap_or(0))
+        .with_cancellation(cancellation);
+    if let Some(seconds) = args.check_timeout {
+        base_request = base_request.with_check_budget(Duration::from_secs(seconds));
+    }
+    let cache = match args.phase {
+        ScanPhase::Static if !args.no_cache => {
+            let cache = Arc::new(load_result_cache(&path, args.policy.as_deref()));
+            base_request = base_request.with_cache(Arc::clone(&cache));
+            Some(cache)
         }
+        _ => None,
+    };
+    let stream = args.format == Some(ScanFormat::Jsonl);
+    let base_request = if stream || args.progress {
+        base_request.with_observer(Arc::new(ScanEvents::new(stream, args.progress)))
+    } else {
+        base_request
     };
+    let workspace = match (args.phase, args.target) {
+        (ScanPhase::Static, _) | (_, TargetSelection::Auto) => Some(
+            base_request
+                .discover_workspace()
+                .with_context(|| format!("failed to walk scan path {}", path.display()))?,
+        ),
+        (ScanPhase::NonStatic, _) => None,
+    };
+    let targets = match args.target {
+        TargetSelection::Web => vec![TargetKind::Web],
+        TargetSelection::Mobile => vec![TargetKind::Mobile],
+        TargetSelection::All => orchestrator.targets(),
+        TargetSelection::Auto => workspace
+            .as_ref()
+            .map(|workspace| orchestrator.detect_targets(workspace))
+            .unwrap_or_default(),
+    };
+    let baseline = args.baseline.as_deref().map(load_baseline).transpose()?;
+    let catalog = match &args.env {
+        Some(_) => load_catalog(&path)?,
+        None => None,
+    };
+
+    let mut reports = Vec::new();
+    let mut effective_policies = Vec::new();
+    let mut checks = Vec::new();
+    for target in targets {
+        checks.extend(
+            orchestrator
+                .engine_for(target)
+                .map(|engine| engine.checks())
+                .unwrap_or_default(),
+        );
+        let effective_policy = args
+            .env
+            .as_deref()
+            .map(|env| resolve_effective_policy(args.policy.as_deref(), &path, env, target))
+            .transpose()?;
+        let mut request = base_request.clone();
+        request.target = target;
+        if let Some(effective) = &effective_policy {
+            request = request.with_disabled_checks(effective.disabled_rules());
+        }
+
+        let mut report = match (args.phase, &workspace) {
+            (ScanPhase::Static, Some(workspace)) => orchestrator
+                .scan_workspace(&request, workspace)
+                .with_context(|| format!("failed to run scan checks for {}", path.display()))?,
+            _ => {
+                let mut report = ScanReport::new(target);
+                report.recompute_status();
+                report
+            }
+        };
+
+        if let Some(effective) = &effective_policy {
+            report = report.with_policy(effective.policy_result());
+            effective.apply_rule_overrides(&mut report);
+            apply_exceptions(
+                args.exceptions.as_deref(),
+                &path,
+                &effective.env,
+                catalog.as_ref(),
+                &mut report,
+            )?;
+            if let Some(metadata) = &mut report.metadata {
+                metadata.env = Some(effective.env.clone());
+                metadata.policy_file = Some(policy_file_path(args.policy.as_deref(), &path));
+                metadata.catalog_version = catalog.as_ref().map(|catalog| catalog.version);
+            }
+            effective_policies.push(effective.clone());
+        }
+
+        if let Some(baseline) = &baseline {
+            report.apply_baseline(&baseline.for_target(target));
+        }
+        reports.push(report);
+    }
+
+    let cache_warning = cache.and_then(|cache| {
+        let cache_path = result_cache_path(&path);
+        save_result_cache(&cache_path, &cache).err().map(|err| {
+            ScanWarning::new(
+                "cache.write-failed",
+                format!("Cache de resultados nao foi gravado: {err:#}"),
+            )
+            .with_subject(cache_path.display().to_string())
+        })
+    });
+
+    let (mut summary, effective_policy) = if args.target.is_combined() {
+        let mut combined = CombinedReport::from_reports(reports);
+        // `auto` may have missed targets whose files the stopped walk never reached.
+        if workspace
+            .as_ref()
+            .is_some_and(|workspace| !workspace.is_complete())
+        {
+            combined.incomplete = true;
+            combined.status = combined.status.max(ScanStatus::Warn);
+        }
+        if combined.targets.is_empty() {
+            combined.warnings.push(ScanWarning::new(
+                "scan.no-targets",
+                "Nenhum projeto web ou mobile detectado no caminho informado.",
+            ));
+        }
+        (ScanSummary::Combined(combined), None)
+    } else {
+        let report = reports.pop().context("scan produced no report")?;
+        (ScanSummary::Single(report), effective_policies.pop())
+    };
+
+    if let Some(warning) = cache_warning {
+        summary.warnings_mut().push(warning);
+    }
 
-    Ok(CommandOutput::Scan(ScanCommandOutput {
+    Ok(CommandOutput::Scan(Box::new(ScanCommandOutput {
         target: args.target,
         phase: args.phase,
         path,
         profile: args.profile,
         config: args.config,
-        summary: ScanSummaryOutput::from(summary),
+        env: args.env,
+        effective_policy,
+        effective_policies,
+        summary,
+        checks,
+    })))
+}
+
+/// Directory holding `.panthereyes/` for a scan path (the path itself, or its parent for files).
+fn config_root(path: &Path) -> &Path {
+    if path.is_file() {
+        path.parent().unwrap_or(Path::new("."))
+    } else {
+        path
+    }
+}
+
+fn apply_exceptions(
+    exceptions_path: Option<&Path>,
+    scan_path: &Path,
+    env: &str,
+    catalog: Option<&RulesFile>,
+    report: &mut ScanReport,
+) -> Result<()> {
+    let exceptions_path = match exceptions_path {
+        Some(path) => path.to_path_buf(),
+        None => {
+            let default_path = config_file(config_root(scan_path), EXCEPTIONS_FILE_NAME);
+            if !default_path.exists() {
+                return Ok(());
+            }
+            default_path
+        }
+    };
+
+    let exceptions = read_exceptions_file(&exceptions_path)?;
+    let mut matcher = ExceptionMatcher::new(&exceptions, env, report.target, CalendarDate::today());
+    if let Some(catalog) = catalog {
+        matcher = matcher.with_catalog(catalog);
+    }
+    matcher.apply(report);
+    Ok(())
+}
+
+/// Rule catalog of the scan path, if it has one.
+fn load_catalog(scan_path: &Path) -> Result<Option<RulesFile>> {
+    let rules_path = config_file(config_root(scan_path), RULES_FILE_NAME);
+    if !rules_path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(read_rules_file(&rules_path)?))
+}
+
+fn policy_file_path(policy_path: Option<&Path>, scan_path: &Path) -> PathBuf {
+    policy_path
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| config_file(config_root(scan_path), POLICY_FILE_NAME))
+}
+
+fn resolve_effective_policy(
+    policy_path: Option<&Path>,
+    scan_path: &Path,
+    env: &str,
+    target: TargetKind,
+) -> Result<EffectivePolicy> {
+    let policy_path = policy_file_path(policy_path, scan_path);
+    let policy = read_policy_file(&policy_path)?;
+    policy
+        .resolve(env, target)
+        .with_context(|| format!("failed to resolve policy from {}", policy_path.display()))
+}
+
+fn handle_baseline_create(
+    args: BaselineCreateArgs,
+    registry: &CheckRegistry,
+) -> Result<CommandOutput> {
+    let path = args.path;
+
+    if !path.exists() {
+        return Err(CliError::ScanPathNotFound { path }.into());
+    }
+
+    let request = ScanRequest::new(scan_target_kind(args.target), &path)
+        .with_workspace(args.workspace.options());
+    let report = build_orchestrator(registry)
+        .scan(&request)
+        .with_context(|| format!("failed to run scan checks for {}", path.display()))?;
+    let baseline = Baseline::from_report(&report);
+    let output = args.output.unwrap_or_else(|| {
+        config_root(&path)
+            .join(".panthereyes")
+            .join("baseline.json")
+    });
+    write_baseline(&output, &baseline)?;
+
+    Ok(CommandOutput::BaselineCreate(BaselineCreateOutput {
+        target: args.target,
+        path,
+        output,
+        findings: baseline.findings.len(),
     }))
 }
 
-fn handle_config_validate(args: ConfigValidateArgs) -> Result<CommandOutput> {
+fn load_baseline(path: &Path) -> Result<Baseline> {
+    if !path.exists() {
+        return Err(CliError::BaselineNotFound {
+            path: path.to_path_buf(),
+        }
+        .into());
+    }
+
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read baseline file {}", path.display()))?;
+    let baseline: Baseline = serde_json::from_str(&raw)
+        .with_context(|| format!("invalid baseline file {}", path.display()))?;
+
+    if baseline.version != BASELINE_VERSION {
+        return Err(CliError::UnsupportedBaselineVersion {
+            path: path.to_path_buf(),
+            version: baseline.version,
+            expected: BASELINE_VERSION,
+        }
+        .into());
+    }
+
+    Ok(baseline)
+}
+
+fn result_cache_path(scan_path: &Path) -> PathBuf {
+    config_root(scan_path)
+        .join(RESULT_CACHE_DIR)
+        .join("results.json")
+}
+
+/// Result cache of the scan path. A missing, unreadable or outdated cache
+/// file just means starting empty.
+fn load_result_cache(scan_path: &Path, policy_path: Option<&Path>) -> ResultCache {
+    let key = result_cache_key(scan_path, policy_path);
+    std::fs::read_to_string(result_cache_path(scan_path))
+        .ok()
+        .and_then(|raw| serde_json::from_str::<ResultCacheFile>(&raw).ok())
+        .map(|file| ResultCache::from_file(file, key.clone()))
+        .unwrap_or_else(|| ResultCache::new(key))
+}
+
+/// Fingerprint of what cached findings depend on besides file contents: the
+/// binary, the policy and the rule catalog. Any change discards the cache.
+fn result_cache_key(scan_path: &Path, policy_path: Option<&Path>) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(concat!("panthereyes ", env!("CARGO_PKG_VERSION"), "\n"));
+    if let Ok(metadata) = std::env::current_exe().and_then(std::fs::metadata) {
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+            .unwrap_or_default();
+        hasher.update(format!(
+            "binary {} {}\n",
+            metadata.len(),
+            modified.as_nanos()
+        ));
+    }
+    let root = config_root(scan_path);
+    for file in [
+        policy_file_path(policy_path, scan_path),
+        config_file(root, RULES_FILE_NAME),
+    ] {
+        if let Ok(contents) = std::fs::read(&file) {
+            hasher.update(format!("{} {}\n", file.display(), contents.len()));
+            hasher.update(contents);
+        }
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn save_result_cache(path: &Path, cache: &ResultCache) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create directory {}", parent.display()))?;
+    }
+    let body =
+        serde_json::to_string(&cache.to_file()).context("failed to serialize result cache")?;
+    std::fs::write(path, format!("{body}\n"))
+        .with_context(|| format!("failed to write result cache {}", path.display()))
+}
+
+fn write_baseline(path: &Path, baseline: &Baseline) -> Result<()> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create directory {}", parent.display()))?;
+    }
+
+    let body = serde_json::to_string_pretty(baseline).context("failed to serialize baseline")?;
+    std::fs::write(path, format!("{body}\n"))
+        .with_context(|| format!("failed to write baseline file {}", path.display()))
+}
+
+fn handle_config_validate(
+    args: ConfigValidateArgs,
+    registry: &CheckRegistry,
+) -> Result<CommandOutput> {
     let path = args.path;
 
     if !path.exists() {
@@ -237,15 +842,65 @@ fn handle_config_validate(args: ConfigValidateArgs) -> Result<CommandOutput> {
     let bytes = std::fs::read(&path)
         .with_context(|| format!("failed to read config file {}", path.display()))?;
 
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| name.to_ascii_lowercase());
+    let mut notes = Vec::new();
+    let mut catalog = None;
+    match file_name.as_deref() {
+        Some(POLICY_FILE_NAME) => {
+            let raw = String::from_utf8_lossy(&bytes);
+            let policy = parse_policy_yaml(&raw, &path)?;
+            notes.push(format!(
+                "Policy schema valido (version {}, envs: {}).",
+                policy.version,
+                policy.env_names().join(", ")
+            ));
+        }
+        Some(EXCEPTIONS_FILE_NAME) => {
+            let raw = String::from_utf8_lossy(&bytes);
+            let exceptions = parse_exceptions_yaml(&raw, &path)?;
+            let today = CalendarDate::today();
+            let expired = exceptions
+                .exceptions
+                .iter()
+                .filter(|entry| !entry.is_active_on(today))
+                .count();
+            notes.push(format!(
+                "Exceptions schema valido ({} excecoes, {expired} expiradas).",
+                exceptions.exceptions.len()
+            ));
+        }
+        Some(RULES_FILE_NAME) => {
+            let raw = String::from_utf8_lossy(&bytes);
+            let rules = parse_rules_yaml(&raw, &path)?;
+            let validation = rules.validate_against(&build_orchestrator(registry).checks());
+            notes.push(format!(
+                "Rule catalog schema valido ({} regras).",
+                rules.rules.len()
+            ));
+            for rule_id in &validation.unimplemented_rules {
+                notes.push(format!("Regra {rule_id} sem check implementado no engine."));
+            }
+            for check_id in &validation.uncataloged_checks {
+                notes.push(format!(
+                    "Check {check_id} sem regra correspondente no catalogo."
+                ));
+            }
+            catalog = Some(validation);
+        }
+        _ => notes.push("Schema validation ainda nao implementada (scaffold).".to_string()),
+    }
+    notes.push("Extensao reconhecida e arquivo acessivel.".to_string());
+
     Ok(CommandOutput::ConfigValidate(ConfigValidateOutput {
         path,
         valid: true,
         bytes: bytes.len(),
         format: extension.unwrap_or_else(|| "unknown".to_string()),
-        notes: vec![
-            "Schema validation ainda nao implementada (scaffold).".to_string(),
-            "Extensao reconhecida e arquivo acessivel.".to_string(),
-        ],
+        notes,
+        catalog,
     }))
 }
 
@@ -318,60 +973,69 @@ fn handle_doctor(args: DoctorArgs) -> Result<CommandOutput> {
 #[derive(Debug, Serialize)]
 #[serde(tag = "command", rename_all = "kebab-case")]
 enum CommandOutput {
-    Scan(ScanCommandOutput),
+    Scan(Box<ScanCommandOutput>),
     ConfigValidate(ConfigValidateOutput),
     PolicyPreview(PolicyPreviewOutput),
     Doctor(DoctorOutput),
+    BaselineCreate(BaselineCreateOutput),
 }
 
 #[derive(Debug, Serialize)]
 struct ScanCommandOutput {
-    target: ScanTarget,
+    target: TargetSelection,
     phase: ScanPhase,
     path: PathBuf,
     profile: Option<String>,
     config: Option<PathBuf>,
-    summary: ScanSummaryOutput,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    env: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    effective_policy: Option<EffectivePolicy>,
+    /// One policy per target for `--target all|auto`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    effective_policies: Vec<EffectivePolicy>,
+    summary: ScanSummary,
+    /// Checks of the scanned targets, for formats that list them.
+    #[serde(skip)]
+    checks: Vec<CheckMetadata>,
 }
 
+/// A single-target report, or the merged report of `--target all|auto`.
 #[derive(Debug, Serialize)]
-struct ScanSummaryOutput {
-    target: String,
-    status: String,
-    findings: Vec<FindingOutput>,
-}
-
-impl From<ScanReport> for ScanSummaryOutput {
-    fn from(summary: ScanReport) -> Self {
-        Self {
-            target: target_label(summary.target).to_string(),
-            status: scan_status_label(summary.status).to_string(),
-            findings: summary
-                .findings
-                .into_iter()
-                .map(|finding| FindingOutput {
-                    id: finding.id,
-                    title: finding.title,
-                    severity: severity_label(finding.severity).to_string(),
-                    target: target_label(finding.target).to_string(),
-                    file: finding.file.map(|path| path.display().to_string()),
-                    message: finding.message,
-                    remediation: finding.remediation,
-                })
-                .collect(),
+#[serde(untagged)]
+enum ScanSummary {
+    Single(ScanReport),
+    Combined(CombinedReport),
+}
+
+impl ScanSummary {
+    fn status(&self) -> ScanStatus {
+        match self {
+            Self::Single(report) => report.status,
+            Self::Combined(report) => report.status,
         }
     }
-}
 
-#[derive(Debug, Serialize)]
-struct FindingOutput {
-    id: String,
-    title: String,
-    severity: String,
-    target: String,
-    file: Option<String>,
-    message: String,
-    remediation: String,
+    fn findings(&self) -> &[Finding] {
+        match self {
+            Self::Single(report) => &report.findings,
+            Self::Combined(report) => &report.findings,
+        }
+    }
+
+    fn suppressed(&self) -> &[Finding] {
+        match self {
+            Self::Single(report) => &report.suppressed,
+            Self::Combined(report) => &report.suppressed,
+        }
+    }
+
+    fn warnings_mut(&mut self) -> &mut Vec<ScanWarning> {
+        match self {
+            Self::Single(report) => &mut report.warnings,
+            Self::Combined(report) => &mut report.warnings,
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -381,6 +1045,8 @@ struct ConfigValidateOutput {
     bytes: usize,
     format: String,
     notes: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    catalog: Option<CatalogValidation>,
 }
 
 #[derive(Debug, Serialize)]
@@ -393,6 +1059,14 @@ struct PolicyPreviewOutput {
     estimated_sources: usize,
 }
 
+#[derive(Debug, Serialize)]
+struct BaselineCreateOutput {
+    target: ScanTarget,
+    path: PathBuf,
+    output: PathBuf,
+    findings: usize,
+}
+
 #[derive(Debug, Serialize)]
 struct DoctorOutput {
     ok: bool,
@@ -406,12 +1080,35 @@ struct DoctorCheck {
     detail: String,
 }
 
-fn print_output(output: &CommandOutput, json: bool) -> Result<()> {
-    if json {
-        let body =
-            serde_json::to_string_pretty(output).context("failed to serialize JSON output")?;
-        println!("{body}");
-        return Ok(());
+/// Whole report in a document format (everything but text and the JSON
+/// Lines stream), ready to print or write to `--output`.
+fn render_document(output: &CommandOutput, format: ScanFormat) -> Result<Option<String>> {
+    let document = match (format, output) {
+        (ScanFormat::Text, _) | (ScanFormat::Jsonl, CommandOutput::Scan(_)) => return Ok(None),
+        (ScanFormat::Sarif, CommandOutput::Scan(value)) => to_json(&SarifLog::from_scan(value))?,
+        (ScanFormat::Junit, CommandOutput::Scan(value)) => junit::render(value),
+        (ScanFormat::Html, CommandOutput::Scan(value)) => html::render(value),
+        (ScanFormat::GitlabCodequality, CommandOutput::Scan(value)) => {
+            to_json(&CodeQualityReport::from_scan(value))?
+        }
+        (ScanFormat::Markdown, CommandOutput::Scan(value)) => {
+            markdown::render(value, markdown::Links::from_env().as_ref())
+        }
+        _ => to_json(output)?,
+    };
+    Ok(Some(document))
+}
+
+fn format_name(format: ScanFormat) -> String {
+    format
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn print_output(output: &CommandOutput, format: ScanFormat) -> Result<()> {
+    if let (ScanFormat::Jsonl, CommandOutput::Scan(value)) = (format, output) {
+        return ScanEvent::Summary(value).emit();
     }
 
     match output {
@@ -419,11 +1116,17 @@ fn print_output(output: &CommandOutput, json: bool) -> Result<()> {
         CommandOutput::ConfigValidate(value) => print_config_validate_text(value),
         CommandOutput::PolicyPreview(value) => print_policy_preview_text(value),
         CommandOutput::Doctor(value) => print_doctor_text(value),
+        CommandOutput::BaselineCreate(value) => print_baseline_create_text(value),
     }
 
     Ok(())
 }
 
+fn to_json(value: &impl Serialize) -> Result<String> {
+    let body = serde_json::to_string_pretty(value).context("failed to serialize JSON output")?;
+    Ok(format!("{body}\n"))
+}
+
 fn print_scan_text(value: &ScanCommandOutput) {
     println!("PantherEyes scan");
     println!("target: {}", value.target.as_str());
@@ -435,19 +1138,197 @@ fn print_scan_text(value: &ScanCommandOutput) {
     if let Some(config) = &value.config {
         println!("config: {}", config.display());
     }
-    println!("status: {}", value.summary.status);
-    println!("findings: {}", value.summary.findings.len());
-    for finding in &value.summary.findings {
+    if let Some(env) = &value.env {
+        println!("env: {env}");
+    }
+    match &value.summary {
+        ScanSummary::Single(report) => print_scan_report_text(report),
+        ScanSummary::Combined(report) => print_combined_report_text(report),
+    }
+}
+
+fn print_scan_report_text(report: &ScanReport) {
+    println!("mode: {}", report.policy.mode.as_str());
+    println!(
+        "fail on severity: {}",
+        severity_label(report.policy.blocking_severity)
+    );
+    if report.policy.status != report.status {
+        println!(
+            "status: {} (evaluated: {})",
+            scan_status_label(report.status),
+            scan_status_label(report.policy.status)
+        );
+    } else {
+        println!("status: {}", scan_status_label(report.status));
+    }
+    match report.policy.min_score {
+        Some(min_score) if report.below_min_score() => println!(
+            "score: {}/100 (minScore {min_score}, abaixo do minimo)",
+            report.score
+        ),
+        Some(min_score) => println!("score: {}/100 (minScore {min_score})", report.score),
+        None => println!("score: {}/100", report.score),
+    }
+    print_incomplete_text(report.incomplete, &report.unfinished_checks);
+    print_findings_text(
+        &report.findings,
+        &report.suppressed,
+        report.baseline.as_ref(),
+        &report.warnings,
+        &report.diagnostics,
+    );
+}
+
+fn print_combined_report_text(report: &CombinedReport) {
+    println!("status: {}", scan_status_label(report.status));
+    println!("score: {}/100", report.score);
+    print_incomplete_text(report.incomplete, &report.unfinished_checks);
+    println!("targets: {}", report.targets.len());
+    for target in &report.targets {
+        let evaluated = if target.policy.status != target.status {
+            format!(" (evaluated: {})", scan_status_label(target.policy.status))
+        } else {
+            String::new()
+        };
+        println!(
+            "- {}: {}{evaluated}, mode {}, score {}/100, findings {}{}",
+            target_label(target.target),
+            scan_status_label(target.status),
+            target.policy.mode.as_str(),
+            target.score,
+            target.findings,
+            if target.incomplete {
+                ", incomplete"
+            } else {
+                ""
+            }
+        );
+    }
+    print_findings_text(
+        &report.findings,
+        &report.suppressed,
+        report.baseline.as_ref(),
+        &report.warnings,
+        &report.diagnostics,
+    );
+}
+
+fn print_incomplete_text(incomplete: bool, unfinished_checks: &[String]) {
+    if !incomplete {
+        return;
+    }
+    println!(
+        "incomplete: scan cancelled or timed out, {} unfinished checks",
+        unfinished_checks.len()
+    );
+    for check in unfinished_checks {
+        println!("- unfinished: {check}");
+    }
+}
+
+fn print_findings_text(
+    findings: &[Finding],
+    suppressed: &[Finding],
+    baseline: Option<&BaselineSummary>,
+    warnings: &[ScanWarning],
+    diagnostics: &[CheckDiagnostic],
+) {
+    println!("findings: {}", findings.len());
+    if let Some(baseline) = baseline {
+        println!(
+            "baseline: {} new, {} existing, {} fixed",
+            baseline.new,
+            baseline.existing,
+            baseline.fixed.len()
+        );
+    }
+    for finding in findings {
         println!(
             "- [{}] {} ({}) :: {}",
-            finding.severity, finding.id, finding.target, finding.title
+            severity_label(finding.severity),
+            finding.id,
+            target_label(finding.target),
+            finding.title
         );
-        if let Some(file) = &finding.file {
-            println!("  file: {file}");
+        if let Some(original) = finding.original_severity {
+            println!(
+                "  severity override: {} -> {}",
+                severity_label(original),
+                severity_label(finding.severity)
+            );
+        }
+        if let Some(rule_id) = &finding.rule_id {
+            println!("  rule: {rule_id}");
+        }
+        if let Some(state) = finding.baseline {
+            println!("  baseline: {}", state.as_str());
+        }
+        if let Some(exception) = &finding.exception {
+            println!("  exception: {exception}");
+        }
+        if let Some(location) = finding.location_label() {
+            println!("  file: {location}");
+        }
+        if let Some(evidence) = &finding.evidence {
+            println!("  evidence: {evidence}");
+        }
+        if let Some(fingerprint) = &finding.fingerprint {
+            println!("  fingerprint: {fingerprint}");
         }
         println!("  message: {}", finding.message);
         println!("  remediation: {}", finding.remediation);
     }
+    if let Some(baseline) = baseline {
+        for entry in &baseline.fixed {
+            let file = entry
+                .file
+                .as_ref()
+                .map(|file| format!(" @ {}", file.display()))
+                .unwrap_or_default();
+            println!("- [fixed] {}{file} :: {}", entry.id, entry.title);
+        }
+    }
+    if !suppressed.is_empty() {
+        println!("suppressed: {}", suppressed.len());
+        for finding in suppressed {
+            let location = finding
+                .location_label()
+                .map(|location| format!(" @ {location}"))
+                .unwrap_or_default();
+            let reason = finding
+                .suppression
+                .as_ref()
+                .map_or("", |suppression| suppression.reason.as_str());
+            println!(
+                "- [{}] {}{location} :: {reason}",
+                severity_label(finding.severity),
+                finding.id
+            );
+        }
+    }
+    if !warnings.is_empty() {
+        println!("warnings: {}", warnings.len());
+        for warning in warnings {
+            println!("- [{}] {}", warning.code, warning.message);
+        }
+    }
+    if !diagnostics.is_empty() {
+        println!("diagnostics: {}", diagnostics.len());
+        for diagnostic in diagnostics {
+            let path = diagnostic
+                .path
+                .as_ref()
+                .map(|path| format!(" @ {}", path.display()))
+                .unwrap_or_default();
+            println!(
+                "- [{}] {}{path}: {}",
+                diagnostic.kind.as_str(),
+                diagnostic.check,
+                diagnostic.message
+            );
+        }
+    }
 }
 
 fn print_config_validate_text(value: &ConfigValidateOutput) {
@@ -478,6 +1359,14 @@ fn print_policy_preview_text(value: &PolicyPreviewOutput) {
     }
 }
 
+fn print_baseline_create_text(value: &BaselineCreateOutput) {
+    println!("PantherEyes baseline create");
+    println!("target: {}", value.target.as_str());
+    println!("path: {}", value.path.display());
+    println!("output: {}", value.output.display());
+    println!("findings: {}", value.findings);
+}
+
 fn print_doctor_text(value: &DoctorOutput) {
     println!("PantherEyes doctor");
     println!("status: {}", if value.ok { "ok" } else { "warn" });
@@ -502,6 +1391,46 @@ fn scan_target_kind(target: ScanTarget) -> TargetKind {
     }
 }
 
+/// Escapes text and attribute values for the XML and HTML reports, dropping
+/// characters XML 1.0 forbids.
+fn escape_markup(raw: &str) -> String {
+    let mut escaped = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' | '\t' | '\r' => escaped.push(c),
+            c if c < ' ' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// `/`-separated path of `file` (relative to the scan `path`) from the
+/// repository root, for links and CI reports. A relative scan path is taken as
+/// relative to the root already; an absolute one must be under `root`.
+fn repository_path(root: Option<&Path>, path: &Path, file: &Path) -> Option<String> {
+    let joined = path.join(file);
+    let relative = if joined.is_absolute() {
+        joined.strip_prefix(root?).ok()?
+    } else {
+        joined.as_path()
+    };
+    let mut parts = Vec::new();
+    for component in relative.components() {
+        match component {
+            std::path::Component::Normal(part) => parts.push(part.to_string_lossy()),
+            std::path::Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(parts.join("/"))
+}
+
 fn target_label(target: TargetKind) -> &'static str {
     target.as_str()
 }
@@ -513,6 +1442,7 @@ fn scan_status_label(status: ScanStatus) -> &'static str {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::TempDir;
 
     #[test]
     fn parses_scan_with_json_flag() {
@@ -532,10 +1462,22 @@ mod tests {
         assert_eq!(
             cli.command,
             Commands::Scan(ScanArgs {
-                target: ScanTarget::Mobile,
+                target: TargetSelection::Mobile,
                 phase: ScanPhase::Static,
                 config: None,
                 profile: Some("ci".to_string()),
+                baseline: None,
+                env: None,
+                policy: None,
+                exceptions: None,
+                workspace: WorkspaceArgs::default(),
+                jobs: None,
+                format: None,
+                output: None,
+                progress: false,
+                timeout: None,
+                check_timeout: None,
+                no_cache: false,
                 path: PathBuf::from("./app.apk"),
             })
         );
@@ -590,6 +1532,222 @@ mod tests {
         assert_eq!(cli.command, Commands::Doctor(DoctorArgs { verbose: true }));
     }
 
+    #[test]
+    fn baseline_create_of_a_file_writes_next_to_it() {
+        let root = TempDir::new("panthereyes-cli-baseline-file");
+        root.write(
+            "AndroidManifest.xml",
+            r#"<application android:debuggable="true"></application>"#,
+        );
+        let args = BaselineCreateArgs {
+            target: ScanTarget::Mobile,
+            output: None,
+            workspace: WorkspaceArgs::default(),
+            path: root.join("AndroidManifest.xml"),
+        };
+
+        let output = handle_baseline_create(args, &CheckRegistry::new()).expect("baseline");
+        let CommandOutput::BaselineCreate(created) = output else {
+            panic!("expected baseline output");
+        };
+        assert_eq!(created.output, root.join(".panthereyes/baseline.json"));
+        assert_eq!(created.findings, 1);
+        assert!(created.output.is_file());
+    }
+
+    #[test]
+    fn parses_baseline_create_and_scan_with_baseline() {
+        let cli = Cli::try_parse_from([
+            "panthereyes",
+            "baseline",
+            "create",
+            "--target",
+            "mobile",
+            "--output",
+            "baseline.json",
+            "./app",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            cli.command,
+            Commands::Baseline(BaselineArgs {
+                command: BaselineCommands::Create(BaselineCreateArgs {
+                    target: ScanTarget::Mobile,
+                    output: Some(PathBuf::from("baseline.json")),
+                    workspace: WorkspaceArgs::default(),
+                    path: PathBuf::from("./app"),
+                }),
+            })
+        );
+
+        let cli = Cli::try_parse_from([
+            "panthereyes",
+            "scan",
+            "--baseline",
+            ".panthereyes/baseline.json",
+        ])
+        .unwrap();
+        let Commands::Scan(args) = cli.command else {
+            panic!("expected scan command");
+        };
+        assert_eq!(
+            args.baseline,
+            Some(PathBuf::from(".panthereyes/baseline.json"))
+        );
+    }
+
+    #[test]
+    fn parses_scan_workspace_filters() {
+        let cli = Cli::try_parse_from([
+            "panthereyes",
+            "scan",
+            "--include",
+            "apps/**",
+            "--include",
+            "**/*.conf",
+            "--exclude",
+            "vendor/**",
+            "--max-file-size",
+            "4096",
+        ])
+        .unwrap();
+        let Commands::Scan(args) = cli.command else {
+            panic!("expected scan command");
+        };
+
+        let options = args.workspace.options();
+        assert_eq!(options.include, ["apps/**", "**/*.conf"]);
+        assert_eq!(options.exclude, ["vendor/**"]);
+        assert_eq!(options.max_file_size, 4096);
+        assert_eq!(
+            WorkspaceArgs::default().options(),
+            WorkspaceOptions::default()
+        );
+    }
+
+    #[test]
+    fn parses_scan_with_policy_env() {
+        let cli = Cli::try_parse_from([
+            "panthereyes",
+            "scan",
+            "--env",
+            "prod",
+            "--policy",
+            "custom/policy.yaml",
+            ".",
+        ])
+        .unwrap();
+        let Commands::Scan(args) = cli.command else {
+            panic!("expected scan command");
+        };
+        assert_eq!(args.env.as_deref(), Some("prod"));
+        assert_eq!(args.policy, Some(PathBuf::from("custom/policy.yaml")));
+
+        assert!(
+            Cli::try_parse_from(["panthereyes", "scan", "--policy", "policy.yaml"]).is_err(),
+            "--policy requires --env"
+        );
+    }
+
+    #[test]
+    fn parses_scan_combined_targets() {
+        for (value, target) in [
+            ("all", TargetSelection::All),
+            ("auto", TargetSelection::Auto),
+        ] {
+            let cli = Cli::try_parse_from(["panthereyes", "scan", "--target", value, "."]).unwrap();
+            let Commands::Scan(args) = cli.command else {
+                panic!("expected scan command");
+            };
+            assert_eq!(args.target, target);
+            assert!(args.target.is_combined());
+        }
+
+        assert!(
+            Cli::try_parse_from(["panthereyes", "baseline", "create", "--target", "all"]).is_err(),
+            "baseline create takes a single target"
+        );
+    }
+
+    #[test]
+    fn parses_scan_jsonl_format_and_progress() {
+        let cli = Cli::try_parse_from([
+            "panthereyes",
+            "scan",
+            "--format",
+            "jsonl",
+            "--progress",
+            ".",
+        ])
+        .unwrap();
+        let Commands::Scan(args) = cli.command else {
+            panic!("expected scan command");
+        };
+        assert_eq!(args.format, Some(ScanFormat::Jsonl));
+        assert!(args.progress);
+    }
+
+    #[test]
+    fn rejects_unknown_scan_format() {
+        assert!(Cli::try_parse_from(["panthereyes", "scan", "--format", "xml", "."]).is_err());
+    }
+
+    #[test]
+    fn parses_scan_timeouts() {
+        let cli = Cli::try_parse_from([
+            "panthereyes",
+            "scan",
+            "--timeout",
+            "300",
+            "--check-timeout",
+            "30",
+            ".",
+        ])
+        .unwrap();
+        let Commands::Scan(args) = cli.command else {
+            panic!("expected scan command");
+        };
+        assert_eq!((args.timeout, args.check_timeout), (Some(300), Some(30)));
+    }
+
+    #[test]
+    fn parses_scan_sarif_format() {
+        let cli = Cli::try_parse_from(["panthereyes", "scan", "--format", "sarif", "."]).unwrap();
+        let Commands::Scan(args) = cli.command else {
+            panic!("expected scan command");
+        };
+        assert_eq!(args.format, Some(ScanFormat::Sarif));
+    }
+
+    #[test]
+    fn parses_scan_junit_format() {
+        let cli = Cli::try_parse_from(["panthereyes", "scan", "--format", "junit", "."]).unwrap();
+        let Commands::Scan(args) = cli.command else {
+            panic!("expected scan command");
+        };
+        assert_eq!(args.format, Some(ScanFormat::Junit));
+    }
+
+    #[test]
+    fn parses_scan_html_output() {
+        let cli = Cli::try_parse_from([
+            "panthereyes",
+            "scan",
+            "--format",
+            "html",
+            "--output",
+            "report.html",
+            ".",
+        ])
+        .unwrap();
+        let Commands::Scan(args) = cli.command else {
+            panic!("expected scan command");
+        };
+        assert_eq!(args.format, Some(ScanFormat::Html));
+        assert_eq!(args.output, Some(PathBuf::from("report.html")));
+    }
+
     #[test]
     fn parses_scan_non_static_phase() {
         let cli = Cli::try_parse_from([
@@ -606,10 +1764,22 @@ mod tests {
         assert_eq!(
             cli.command,
             Commands::Scan(ScanArgs {
-                target: ScanTarget::Web,
+                target: TargetSelection::Web,
                 phase: ScanPhase::NonStatic,
                 config: None,
                 profile: None,
+                baseline: None,
+                env: None,
+                policy: None,
+                exceptions: None,
+                workspace: WorkspaceArgs::default(),
+                jobs: None,
+                format: None,
+                output: None,
+                progress: false,
+                timeout: None,
+                check_timeout: None,
+                no_cache: false,
                 path: PathBuf::from("."),
             })
         );
@@ -1,16 +1,16 @@
 use std::process::ExitCode;
 
 use clap::Parser;
-use panthereyes_cli::{run, Cli};
+use panthereyes_cli::{run, Cli, EXIT_TOOL_ERROR};
 
 fn main() -> ExitCode {
     let cli = Cli::parse();
 
     match run(cli) {
-        Ok(()) => ExitCode::SUCCESS,
+        Ok(code) => code,
         Err(err) => {
             eprintln!("error: {err:#}");
-            ExitCode::FAILURE
+            ExitCode::from(EXIT_TOOL_ERROR)
         }
     }
 }
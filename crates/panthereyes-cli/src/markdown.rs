@@ -0,0 +1,478 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use panthereyes_core::{
+    BaselineEntry, BaselineState, BaselineSummary, Finding, ScanStatus, Severity,
+};
+
+use crate::{config_root, escape_markup, repository_path, ScanCommandOutput, ScanSummary};
+
+/// GitHub rejects comments over 65,536 characters; stay clear of it.
+const MAX_LENGTH: usize = 60_000;
+/// Room kept for closing tags and the truncation note once items stop fitting.
+const RESERVE: usize = 1_000;
+
+const SEVERITIES: [Severity; 4] = [
+    Severity::Critical,
+    Severity::High,
+    Severity::Medium,
+    Severity::Low,
+];
+
+/// Blob URL prefix for file links, e.g. `https://github.com/org/repo/blob/<sha>/`.
+pub(crate) struct Links {
+    base: String,
+    /// Repository checkout that absolute scan paths are made relative to.
+    root: Option<PathBuf>,
+}
+
+impl Links {
+    /// Links to the commit being built when running in GitHub Actions.
+    pub(crate) fn from_env() -> Option<Self> {
+        let var = |name| std::env::var(name).ok().filter(|value| !value.is_empty());
+        Some(Self {
+            base: format!(
+                "{}/{}/blob/{}/",
+                var("GITHUB_SERVER_URL")?.trim_end_matches('/'),
+                var("GITHUB_REPOSITORY")?,
+                var("GITHUB_SHA")?
+            ),
+            root: var("GITHUB_WORKSPACE").map(PathBuf::from),
+        })
+    }
+
+    fn url(&self, scan_path: &Path, finding: &Finding) -> Option<String> {
+        let path = repository_path(
+            self.root.as_deref(),
+            config_root(scan_path),
+            finding.file.as_deref()?,
+        )?;
+        let anchor = finding
+            .span
+            .map(|span| format!("#L{}", span.line))
+            .unwrap_or_default();
+        Some(format!("{}{path}{anchor}", self.base))
+    }
+}
+
+/// `scan --format markdown`: a summary for pull request comments and
+/// `$GITHUB_STEP_SUMMARY`, with a status and severity table, collapsible
+/// finding lists (split into new, existing and fixed against a baseline) and
+/// the lists cut short to keep the document under [`MAX_LENGTH`].
+pub(crate) fn render(output: &ScanCommandOutput, links: Option<&Links>) -> String {
+    let summary = &output.summary;
+    let (status, score, baseline, incomplete, warnings, diagnostics) = match summary {
+        ScanSummary::Single(report) => (
+            report.status,
+            report.score,
+            report.baseline.as_ref(),
+            report.incomplete,
+            report.warnings.len(),
+            report.diagnostics.len(),
+        ),
+        ScanSummary::Combined(report) => (
+            report.status,
+            report.score,
+            report.baseline.as_ref(),
+            report.incomplete,
+            report.warnings.len(),
+            report.diagnostics.len(),
+        ),
+    };
+
+    let mut md = Document::default();
+    let mut context = vec![
+        format!("target `{}`", output.target.as_str()),
+        format!("phase `{}`", output.phase.as_str()),
+    ];
+    if let Some(env) = &output.env {
+        context.push(format!("env `{}`", code(env)));
+    }
+    if let Some(profile) = &output.profile {
+        context.push(format!("profile `{}`", code(profile)));
+    }
+    md.line(format!(
+        "## {} PantherEyes: {}\n",
+        status_icon(status),
+        status.as_str()
+    ));
+    md.line(format!("Score **{score}/100** · {}\n", context.join(" · ")));
+    write_counts(&mut md, summary);
+    if let ScanSummary::Combined(report) = summary {
+        md.line("| Target | Status | Score | Findings |\n| --- | --- | ---: | ---: |");
+        for target in &report.targets {
+            md.line(format!(
+                "| {} | {} {}{} | {}/100 | {} |",
+                target.target.as_str(),
+                status_icon(target.status),
+                target.status.as_str(),
+                if target.incomplete {
+                    " (incomplete)"
+                } else {
+                    ""
+                },
+                target.score,
+                target.findings
+            ));
+        }
+        md.line("");
+    }
+    if incomplete {
+        md.line("> **Incomplete scan:** cancelled or timed out, findings may be missing.\n");
+    }
+    if warnings + diagnostics > 0 {
+        md.line(format!(
+            "{warnings} warnings and {diagnostics} check diagnostics; see the JSON or HTML report.\n"
+        ));
+    }
+
+    let mut findings: Vec<&Finding> = summary.findings().iter().collect();
+    findings.sort_by_key(|finding| {
+        (
+            !finding.counts_toward_status(),
+            std::cmp::Reverse(finding.severity),
+            finding.file.clone(),
+            finding.span.map(|span| span.line),
+        )
+    });
+    match baseline {
+        Some(baseline) => write_baseline(&mut md, output, links, &findings, baseline),
+        None => {
+            let title = format!("Findings ({})", findings.len());
+            md.findings(&title, false, &findings, output, links);
+        }
+    }
+    let suppressed: Vec<&Finding> = summary.suppressed().iter().collect();
+    if !suppressed.is_empty() {
+        let title = format!("Suppressed ({})", suppressed.len());
+        md.findings(&title, false, &suppressed, output, links);
+    }
+
+    if md.omitted > 0 {
+        md.line(format!(
+            "_{} more entries not shown to fit the comment size limit; use `--format html` or `--format sarif` for the full list._\n",
+            md.omitted
+        ));
+    }
+    md.text
+}
+
+fn write_counts(md: &mut Document, summary: &ScanSummary) {
+    let findings = summary.findings();
+    md.line("| Severity | Findings | Gating |\n| --- | ---: | ---: |");
+    for severity in SEVERITIES {
+        let of_severity = || {
+            findings
+                .iter()
+                .filter(move |finding| finding.severity == severity)
+        };
+        md.line(format!(
+            "| {} | {} | {} |",
+            severity.as_str(),
+            of_severity().count(),
+            of_severity()
+                .filter(|finding| finding.counts_toward_status())
+                .count()
+        ));
+    }
+    md.line(format!(
+        "| **total** | **{}** | **{}** |\n",
+        findings.len(),
+        findings
+            .iter()
+            .filter(|finding| finding.counts_toward_status())
+            .count()
+    ));
+}
+
+fn write_baseline(
+    md: &mut Document,
+    output: &ScanCommandOutput,
+    links: Option<&Links>,
+    findings: &[&Finding],
+    baseline: &BaselineSummary,
+) {
+    md.line(format!(
+        "**Against the baseline:** {} new, {} existing, {} fixed\n",
+        baseline.new,
+        baseline.existing,
+        baseline.fixed.len()
+    ));
+    let (existing, new): (Vec<&Finding>, Vec<&Finding>) = findings
+        .iter()
+        .copied()
+        .partition(|finding| finding.baseline == Some(BaselineState::Existing));
+    md.findings(
+        &format!("New findings ({})", new.len()),
+        true,
+        &new,
+        output,
+        links,
+    );
+    if !baseline.fixed.is_empty() {
+        md.open(&format!("Fixed findings ({})", baseline.fixed.len()), false);
+        for entry in &baseline.fixed {
+            md.item(fixed_item(entry));
+        }
+        md.close();
+    }
+    md.findings(
+        &format!("Existing findings ({})", existing.len()),
+        false,
+        &existing,
+        output,
+        links,
+    );
+}
+
+/// Markdown being built; list items are dropped, and counted, once the next
+/// one would take the document past [`MAX_LENGTH`].
+#[derive(Default)]
+struct Document {
+    text: String,
+    omitted: usize,
+    truncated: bool,
+}
+
+impl Document {
+    fn line(&mut self, line: impl AsRef<str>) {
+        self.text.push_str(line.as_ref());
+        self.text.push('\n');
+    }
+
+    fn open(&mut self, title: &str, open: bool) {
+        let _ = writeln!(
+            self.text,
+            "<details{}>\n<summary><strong>{}</strong></summary>\n",
+            if open { " open" } else { "" },
+            escape_markup(title)
+        );
+    }
+
+    fn close(&mut self) {
+        self.text.push_str("\n</details>\n\n");
+    }
+
+    fn item(&mut self, item: String) {
+        if self.truncated || self.text.len() + item.len() + RESERVE > MAX_LENGTH {
+            self.truncated = true;
+            self.omitted += 1;
+            return;
+        }
+        self.text.push_str(&item);
+    }
+
+    fn findings(
+        &mut self,
+        title: &str,
+        open: bool,
+        findings: &[&Finding],
+        output: &ScanCommandOutput,
+        links: Option<&Links>,
+    ) {
+        if findings.is_empty() {
+            return;
+        }
+        self.open(title, open);
+        for finding in findings {
+            self.item(finding_item(finding, output, links));
+        }
+        self.close();
+    }
+}
+
+fn finding_item(finding: &Finding, output: &ScanCommandOutput, links: Option<&Links>) -> String {
+    let mut item = format!(
+        "- {} **{}** `{}` {}",
+        severity_icon(finding.severity),
+        finding.severity.as_str(),
+        code(&finding.id),
+        text(&finding.title)
+    );
+    if let Some(location) = finding.location_label() {
+        let location = format!("`{}`", code(&location));
+        match links.and_then(|links| links.url(&output.path, finding)) {
+            Some(url) => {
+                let _ = write!(item, " in [{location}]({url})");
+            }
+            None => {
+                let _ = write!(item, " in {location}");
+            }
+        }
+    }
+    if matches!(output.summary, ScanSummary::Combined(_)) {
+        let _ = write!(item, " ({})", finding.target.as_str());
+    }
+    let _ = write!(item, "\n  {}", text(&finding.message));
+    if let Some(suppression) = &finding.suppression {
+        let _ = write!(item, "\n  _Suppressed:_ {}", text(&suppression.reason));
+    } else if let Some(exception) = &finding.exception {
+        let _ = write!(item, "\n  _Exception:_ `{}`", code(exception));
+    }
+    let _ = writeln!(item, "\n  _Remediation:_ {}", text(&finding.remediation));
+    item
+}
+
+fn fixed_item(entry: &BaselineEntry) -> String {
+    let mut item = format!(
+        "- {} **{}** `{}` {}",
+        severity_icon(entry.severity),
+        entry.severity.as_str(),
+        code(&entry.id),
+        text(&entry.title)
+    );
+    if let Some(file) = &entry.file {
+        let _ = write!(item, " in `{}`", code(&file.display().to_string()));
+    }
+    item.push('\n');
+    item
+}
+
+fn status_icon(status: ScanStatus) -> &'static str {
+    match status {
+        ScanStatus::Pass => ":white_check_mark:",
+        ScanStatus::Warn => ":warning:",
+        ScanStatus::Block => ":no_entry:",
+    }
+}
+
+fn severity_icon(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => ":red_circle:",
+        Severity::High => ":orange_circle:",
+        Severity::Medium => ":yellow_circle:",
+        Severity::Low => ":white_circle:",
+    }
+}
+
+/// Inline text: HTML and markdown syntax escaped, folded onto one line.
+fn text(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    for c in escape_markup(value).chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '|' | '#' | '~' => {
+                text.push('\\');
+                text.push(c);
+            }
+            '\n' | '\r' => text.push(' '),
+            _ => text.push(c),
+        }
+    }
+    text
+}
+
+/// Contents of a code span, which cannot hold backticks nor line breaks.
+fn code(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '`' => '\'',
+            '\n' | '\r' | '|' => ' ',
+            _ => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{scan_output, TempDir};
+    use panthereyes_core::{ScanReport, SourceSpan, TargetKind};
+
+    fn finding(file: &str, message: &str) -> Finding {
+        Finding::new(
+            "web.headers.csp.missing",
+            "Content-Security-Policy ausente",
+            Severity::High,
+            TargetKind::Web,
+            message,
+            "Adicione um header CSP.",
+        )
+        .with_file(file)
+    }
+
+    fn output(report: ScanReport) -> ScanCommandOutput {
+        let mut output = scan_output(report, Vec::new());
+        output.path = PathBuf::from("./site");
+        output.env = Some("prod".to_string());
+        output
+    }
+
+    #[test]
+    fn splits_findings_against_the_baseline_with_file_links() {
+        let mut report = ScanReport::new(TargetKind::Web);
+        let mut new = finding("nginx.conf", "<b>sem</b> CSP | *header*").with_span(SourceSpan {
+            line: 12,
+            column: 3,
+            end_line: 12,
+            end_column: 9,
+        });
+        new.baseline = Some(BaselineState::New);
+        let mut existing = finding("legacy/nginx.conf", "sem CSP");
+        existing.baseline = Some(BaselineState::Existing);
+        report.push(new);
+        report.push(existing);
+        report.baseline = Some(BaselineSummary {
+            new: 1,
+            existing: 1,
+            fixed: vec![BaselineEntry {
+                fingerprint: "abc".to_string(),
+                id: "web.headers.hsts.missing".to_string(),
+                title: "HSTS ausente".to_string(),
+                severity: Severity::Medium,
+                target: TargetKind::Web,
+                file: Some(PathBuf::from("old.conf")),
+            }],
+        });
+        let links = Links {
+            base: "https://github.com/acme/site/blob/abc123/".to_string(),
+            root: None,
+        };
+
+        let md = render(&output(report), Some(&links));
+        assert!(md.starts_with("## :no_entry: PantherEyes: block\n"));
+        assert!(md.contains("| high | 2 | 1 |"));
+        assert!(md.contains("**Against the baseline:** 1 new, 1 existing, 1 fixed"));
+        assert!(md.contains("<details open>\n<summary><strong>New findings (1)</strong>"));
+        assert!(md.contains(
+            "[`nginx.conf:12:3`](https://github.com/acme/site/blob/abc123/site/nginx.conf#L12)"
+        ));
+        assert!(md.contains("&lt;b&gt;sem&lt;/b&gt; CSP \\| \\*header\\*"));
+        assert!(md.contains("`web.headers.hsts.missing` HSTS ausente in `old.conf`"));
+        assert!(md.contains("<summary><strong>Existing findings (1)</strong>"));
+        assert!(md.contains("_Remediation:_ Adicione um header CSP."));
+    }
+
+    #[test]
+    fn links_findings_of_a_file_scan_from_its_directory() {
+        let root = TempDir::new("panthereyes-markdown-links");
+        root.write("site/nginx.conf", "server {}\n");
+        let mut report = ScanReport::new(TargetKind::Web);
+        report.push(finding("nginx.conf", "sem CSP"));
+        let mut output = output(report);
+        output.path = root.join("site/nginx.conf");
+        let links = Links {
+            base: "https://github.com/acme/site/blob/abc123/".to_string(),
+            root: Some(root.path().to_path_buf()),
+        };
+
+        let md = render(&output, Some(&links));
+        assert!(
+            md.contains("[`nginx.conf`](https://github.com/acme/site/blob/abc123/site/nginx.conf)")
+        );
+    }
+
+    #[test]
+    fn truncates_long_finding_lists_within_the_comment_limit() {
+        let mut report = ScanReport::new(TargetKind::Web);
+        for index in 0..2_000 {
+            report.push(finding(&format!("site-{index}/nginx.conf"), "sem CSP"));
+        }
+
+        let md = render(&output(report), None);
+        assert!(md.len() <= MAX_LENGTH);
+        assert!(md.contains("more entries not shown to fit the comment size limit"));
+        assert!(md.contains("</details>"));
+        assert!(md.contains("| **total** | **2000** | **2000** |"));
+    }
+}
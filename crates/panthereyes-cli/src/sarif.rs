@@ -0,0 +1,415 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use panthereyes_core::{BaselineState, CheckMetadata, Finding, Severity};
+use serde::Serialize;
+
+use crate::{config_root, ScanCommandOutput};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const SRCROOT: &str = "%SRCROOT%";
+/// Key of the finding fingerprint under `partialFingerprints`.
+const FINGERPRINT_KEY: &str = "panthereyes/v1";
+
+/// `scan --format sarif`: a SARIF 2.1.0 log with one run, one rule per check
+/// of the scanned targets and one result per finding. Suppressed and excepted
+/// findings are kept as results carrying a `suppressions` entry.
+#[derive(Debug, Serialize)]
+pub(crate) struct SarifLog<'a> {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<Run<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Run<'a> {
+    tool: Tool<'a>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    original_uri_base_ids: BTreeMap<&'static str, ArtifactLocation>,
+    results: Vec<SarifResult<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct Tool<'a> {
+    driver: Driver<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct Driver<'a> {
+    name: &'static str,
+    version: &'static str,
+    rules: Vec<Rule<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Rule<'a> {
+    id: &'a str,
+    short_description: Message<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    full_description: Option<Message<'a>>,
+    help: Message<'a>,
+    default_configuration: Configuration,
+    properties: RuleProperties<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct Configuration {
+    level: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct RuleProperties<'a> {
+    tags: Vec<&'a str>,
+    /// Catalog rule implemented by the check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_id: Option<&'a str>,
+    /// Read by GitHub code scanning to rank security alerts.
+    #[serde(rename = "security-severity")]
+    security_severity: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct Message<'a> {
+    text: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult<'a> {
+    rule_id: &'a str,
+    rule_index: usize,
+    level: &'static str,
+    message: Message<'a>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    locations: Vec<Location<'a>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    partial_fingerprints: BTreeMap<&'static str, &'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    baseline_state: Option<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    suppressions: Vec<Suppression<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Location<'a> {
+    physical_location: PhysicalLocation<'a>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhysicalLocation<'a> {
+    artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<Region<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ArtifactLocation {
+    uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri_base_id: Option<&'static str>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Region<'a> {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    snippet: Option<Message<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct Suppression<'a> {
+    kind: &'static str,
+    justification: &'a str,
+}
+
+impl<'a> SarifLog<'a> {
+    pub(crate) fn from_scan(output: &'a ScanCommandOutput) -> Self {
+        let findings = output.summary.findings();
+        let suppressed = output.summary.suppressed();
+
+        let mut rules: Vec<Rule<'a>> = output
+            .checks
+            .iter()
+            .map(|check| rule_for_check(check, findings.iter().chain(suppressed)))
+            .collect();
+        let mut results = Vec::new();
+        for finding in findings.iter().chain(suppressed) {
+            let rule_index = match rules.iter().position(|rule| rule.id == finding.id) {
+                Some(index) => index,
+                None => {
+                    rules.push(rule_for_finding(finding));
+                    rules.len() - 1
+                }
+            };
+            results.push(result_for(finding, rule_index));
+        }
+
+        let mut original_uri_base_ids = BTreeMap::new();
+        if let Some(uri) = directory_uri(config_root(&output.path)) {
+            original_uri_base_ids.insert(
+                SRCROOT,
+                ArtifactLocation {
+                    uri,
+                    uri_base_id: None,
+                },
+            );
+        }
+
+        Self {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![Run {
+                tool: Tool {
+                    driver: Driver {
+                        name: "PantherEyes",
+                        version: env!("CARGO_PKG_VERSION"),
+                        rules,
+                    },
+                },
+                original_uri_base_ids,
+                results,
+            }],
+        }
+    }
+}
+
+/// Rule of a check; the help text is the remediation of its first finding,
+/// or the check description when it did not fire.
+fn rule_for_check<'a>(
+    check: &'a CheckMetadata,
+    mut findings: impl Iterator<Item = &'a Finding>,
+) -> Rule<'a> {
+    let remediation = findings
+        .find(|finding| finding.id == check.id)
+        .map(|finding| finding.remediation.as_str());
+    Rule {
+        id: check.id,
+        short_description: Message { text: check.title },
+        full_description: Some(Message {
+            text: check.description,
+        }),
+        help: Message {
+            text: remediation.unwrap_or(check.description),
+        },
+        default_configuration: Configuration {
+            level: level(check.default_severity),
+        },
+        properties: RuleProperties {
+            tags: check.tags.to_vec(),
+            rule_id: Some(check.rule_id),
+            security_severity: security_severity(check.default_severity),
+        },
+    }
+}
+
+/// Rule for a finding whose check is not among the scanned engines' checks.
+fn rule_for_finding(finding: &Finding) -> Rule<'_> {
+    Rule {
+        id: &finding.id,
+        short_description: Message {
+            text: &finding.title,
+        },
+        full_description: None,
+        help: Message {
+            text: &finding.remediation,
+        },
+        default_configuration: Configuration {
+            level: level(finding.severity),
+        },
+        properties: RuleProperties {
+            tags: Vec::new(),
+            rule_id: finding.rule_id.as_deref(),
+            security_severity: security_severity(finding.severity),
+        },
+    }
+}
+
+fn result_for(finding: &Finding, rule_index: usize) -> SarifResult<'_> {
+    let locations = finding
+        .file
+        .as_deref()
+        .map(|file| Location {
+            physical_location: PhysicalLocation {
+                artifact_location: ArtifactLocation {
+                    uri: relative_uri(file),
+                    uri_base_id: Some(SRCROOT),
+                },
+                region: finding.span.map(|span| Region {
+                    start_line: span.line,
+                    start_column: span.column,
+                    end_line: span.end_line,
+                    end_column: span.end_column,
+                    snippet: finding.evidence.as_deref().map(|text| Message { text }),
+                }),
+            },
+        })
+        .into_iter()
+        .collect();
+
+    let mut suppressions = Vec::new();
+    if let Some(suppression) = &finding.suppression {
+        suppressions.push(Suppression {
+            kind: "inSource",
+            justification: &suppression.reason,
+        });
+    }
+    if let Some(exception) = &finding.exception {
+        suppressions.push(Suppression {
+            kind: "external",
+            justification: exception,
+        });
+    }
+
+    SarifResult {
+        rule_id: &finding.id,
+        rule_index,
+        level: level(finding.severity),
+        message: Message {
+            text: &finding.message,
+        },
+        locations,
+        partial_fingerprints: finding
+            .fingerprint
+            .as_deref()
+            .map(|fingerprint| (FINGERPRINT_KEY, fingerprint))
+            .into_iter()
+            .collect(),
+        baseline_state: finding.baseline.map(|state| match state {
+            BaselineState::New => "new",
+            BaselineState::Existing => "unchanged",
+        }),
+        suppressions,
+    }
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::High => "error",
+        Severity::Medium => "warning",
+        Severity::Low => "note",
+    }
+}
+
+/// GitHub buckets: critical >= 9.0, high >= 7.0, medium >= 4.0, low below.
+fn security_severity(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical => "9.5",
+        Severity::High => "8.0",
+        Severity::Medium => "5.5",
+        Severity::Low => "2.0",
+    }
+}
+
+/// `file://` URI of the scan root, with a trailing slash as SARIF requires
+/// for base ids. `None` when the path cannot be resolved.
+fn directory_uri(root: &Path) -> Option<String> {
+    let root = root.canonicalize().ok()?;
+    let path = root.to_string_lossy().replace('\\', "/");
+    let path = path.trim_end_matches('/');
+    let separator = if path.starts_with('/') { "" } else { "/" };
+    Some(format!("file://{separator}{}/", percent_encode(path)))
+}
+
+fn relative_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    percent_encode(path.trim_start_matches("./"))
+}
+
+/// Escapes everything but unreserved characters, `/` and `:`.
+fn percent_encode(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scan_output;
+    use panthereyes_checks_web::WebChecksEngine;
+    use panthereyes_core::{InlineSuppression, ScanEngine, ScanReport, SourceSpan, TargetKind};
+
+    #[test]
+    fn maps_checks_to_rules_and_findings_to_results() {
+        let checks = WebChecksEngine::default().checks();
+        let mut report = ScanReport::new(TargetKind::Web);
+        let finding = |id: &str, file: &str| {
+            Finding::new(
+                id,
+                "t",
+                Severity::Medium,
+                TargetKind::Web,
+                "m",
+                "Configure HSTS.",
+            )
+            .with_file(file)
+            .with_span(SourceSpan {
+                line: 3,
+                column: 5,
+                end_line: 3,
+                end_column: 15,
+            })
+        };
+        report.push(finding("web.headers.hsts.missing", "conf/my site.conf"));
+        let mut suppressed = finding("web.headers.hsts.missing", "other.conf");
+        suppressed.ensure_fingerprint();
+        suppressed.suppression = Some(InlineSuppression {
+            line: 2,
+            rules: vec!["web.hsts.required".to_string()],
+            reason: "TLS no LB".to_string(),
+        });
+        report.suppressed.push(suppressed);
+        report.push(finding("custom.extra", "app.conf"));
+        let output = scan_output(report, checks);
+
+        let log = serde_json::to_value(SarifLog::from_scan(&output)).expect("serialize");
+        assert_eq!(log["version"], "2.1.0");
+        let run = &log["runs"][0];
+        let rules = run["tool"]["driver"]["rules"].as_array().expect("rules");
+        let ids: Vec<_> = rules.iter().map(|rule| rule["id"].as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                Some("web.headers.csp.missing"),
+                Some("web.headers.hsts.missing"),
+                Some("custom.extra")
+            ]
+        );
+        assert_eq!(rules[1]["help"]["text"], "Configure HSTS.");
+        assert_eq!(rules[1]["defaultConfiguration"]["level"], "warning");
+        assert_eq!(rules[0]["properties"]["tags"][2], "xss");
+        assert!(run["originalUriBaseIds"]["%SRCROOT%"]["uri"]
+            .as_str()
+            .is_some_and(|uri| uri.starts_with("file:///") && uri.ends_with('/')));
+
+        let results = run["results"].as_array().expect("results");
+        assert_eq!(results.len(), 3);
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "conf/my%20site.conf");
+        assert_eq!(location["artifactLocation"]["uriBaseId"], "%SRCROOT%");
+        assert_eq!(location["region"]["startLine"], 3);
+        assert_eq!(results[0]["ruleIndex"], 1);
+        assert!(results[0]["partialFingerprints"][FINGERPRINT_KEY].is_string());
+        assert_eq!(results[1]["ruleIndex"], 2);
+        assert_eq!(results[2]["suppressions"][0]["kind"], "inSource");
+        assert_eq!(results[2]["suppressions"][0]["justification"], "TLS no LB");
+    }
+}
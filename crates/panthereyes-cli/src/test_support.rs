@@ -0,0 +1,85 @@
+//! Fixtures shared by the unit tests of the crate.
+
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use panthereyes_core::{CheckMetadata, ScanReport, TargetKind};
+
+use crate::{ScanCommandOutput, ScanPhase, ScanSummary, TargetSelection};
+
+/// Static scan of `.` that produced `report`, with no profile, env or
+/// policy; tests set the fields they exercise on the result.
+pub(crate) fn scan_output(report: ScanReport, checks: Vec<CheckMetadata>) -> ScanCommandOutput {
+    ScanCommandOutput {
+        target: match report.target {
+            TargetKind::Web => TargetSelection::Web,
+            TargetKind::Mobile | TargetKind::Ios | TargetKind::Android => TargetSelection::Mobile,
+        },
+        phase: ScanPhase::Static,
+        path: PathBuf::from("."),
+        profile: None,
+        config: None,
+        env: None,
+        effective_policy: None,
+        effective_policies: Vec::new(),
+        summary: ScanSummary::Single(report),
+        checks,
+    }
+}
+
+static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Directory under the system temp dir, unique to the call and removed when
+/// dropped, also when the test panics.
+pub(crate) struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub(crate) fn new(prefix: &str) -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time")
+            .as_nanos();
+        let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!(
+            "{}-{}-{}-{}",
+            prefix,
+            std::process::id(),
+            nanos,
+            seq
+        ));
+        fs::create_dir_all(&path).expect("create temp dir");
+        Self { path }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes `contents` at `relative`, creating its parent directories.
+    pub(crate) fn write(&self, relative: impl AsRef<Path>, contents: impl AsRef<[u8]>) {
+        let path = self.path.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("create dirs");
+        }
+        fs::write(path, contents).expect("write file");
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.path).ok();
+    }
+}
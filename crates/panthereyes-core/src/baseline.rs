@@ -0,0 +1,212 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use crate::{Finding, ScanReport, Severity, TargetKind};
+
+pub const BASELINE_VERSION: u32 = 1;
+
+/// Accepted snapshot of findings, keyed by fingerprint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Baseline {
+    pub version: u32,
+    pub target: TargetKind,
+    pub findings: Vec<BaselineEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BaselineEntry {
+    pub fingerprint: String,
+    pub id: String,
+    pub title: String,
+    pub severity: Severity,
+    pub target: TargetKind,
+    pub file: Option<PathBuf>,
+}
+
+impl BaselineEntry {
+    fn from_finding(finding: &Finding) -> Self {
+        Self {
+            fingerprint: finding
+                .fingerprint
+                .clone()
+                .unwrap_or_else(|| finding.compute_fingerprint()),
+            id: finding.id.clone(),
+            title: finding.title.clone(),
+            severity: finding.severity,
+            target: finding.target,
+            file: finding.file.clone(),
+        }
+    }
+}
+
+impl Baseline {
+    pub fn from_report(report: &ScanReport) -> Self {
+        let mut seen = HashSet::new();
+        let mut findings: Vec<BaselineEntry> = report
+            .findings
+            .iter()
+            .map(BaselineEntry::from_finding)
+            .filter(|entry| seen.insert(entry.fingerprint.clone()))
+            .collect();
+        findings.sort_by(|a, b| {
+            (&a.id, &a.file, &a.fingerprint).cmp(&(&b.id, &b.file, &b.fingerprint))
+        });
+
+        Self {
+            version: BASELINE_VERSION,
+            target: report.target,
+            findings,
+        }
+    }
+
+    /// Entries scanned by the engine of `target`, so a multi-target scan does
+    /// not report another target's entries as fixed.
+    pub fn for_target(&self, target: TargetKind) -> Self {
+        let target = target.engine_target();
+        Self {
+            version: self.version,
+            target,
+            findings: self
+                .findings
+                .iter()
+                .filter(|entry| entry.target.engine_target() == target)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn contains(&self, fingerprint: &str) -> bool {
+        self.findings
+            .iter()
+            .any(|entry| entry.fingerprint == fingerprint)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum BaselineState {
+    New,
+    Existing,
+}
+
+impl BaselineState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::New => "new",
+            Self::Existing => "existing",
+        }
+    }
+}
+
+/// Outcome of comparing a scan against a baseline. Fixed entries are baseline
+/// findings that no longer show up in the scan.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BaselineSummary {
+    pub new: usize,
+    pub existing: usize,
+    pub fixed: Vec<BaselineEntry>,
+}
+
+impl ScanReport {
+    /// Classifies every finding as new or existing and records fixed entries.
+    /// Only new findings count toward the scan status afterwards.
+    pub fn apply_baseline(&mut self, baseline: &Baseline) {
+        let mut current = HashSet::new();
+        let mut summary = BaselineSummary::default();
+
+        for finding in &mut self.findings {
+            let fingerprint = finding.ensure_fingerprint().to_string();
+            let state = if baseline.contains(&fingerprint) {
+                summary.existing += 1;
+                BaselineState::Existing
+            } else {
+                summary.new += 1;
+                BaselineState::New
+            };
+            finding.baseline = Some(state);
+            current.insert(fingerprint);
+        }
+
+        summary.fixed = baseline
+            .findings
+            .iter()
+            .filter(|entry| !current.contains(&entry.fingerprint))
+            .cloned()
+            .collect();
+
+        self.baseline = Some(summary);
+        self.recompute_status();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ScanStatus;
+
+    fn finding(id: &str, severity: Severity, file: &str) -> Finding {
+        Finding::new(id, id, severity, TargetKind::Web, "message", "remediation").with_file(file)
+    }
+
+    #[test]
+    fn status_is_derived_from_new_findings_only() {
+        let mut accepted = ScanReport::new(TargetKind::Web);
+        accepted.push(finding(
+            "web.headers.csp.missing",
+            Severity::High,
+            "nginx.conf",
+        ));
+        accepted.push(finding(
+            "web.headers.hsts.missing",
+            Severity::Medium,
+            "old.conf",
+        ));
+        let baseline = Baseline::from_report(&accepted);
+
+        let mut report = ScanReport::new(TargetKind::Web);
+        report.push(finding(
+            "web.headers.csp.missing",
+            Severity::High,
+            "nginx.conf",
+        ));
+        report.push(finding(
+            "web.headers.hsts.missing",
+            Severity::Medium,
+            "new.conf",
+        ));
+        assert_eq!(report.status, ScanStatus::Block);
+
+        report.apply_baseline(&baseline);
+
+        let summary = report.baseline.as_ref().expect("baseline summary");
+        assert_eq!((summary.new, summary.existing), (1, 1));
+        assert_eq!(summary.fixed.len(), 1);
+        assert_eq!(
+            summary.fixed[0].file.as_deref(),
+            Some(std::path::Path::new("old.conf"))
+        );
+        assert_eq!(report.findings[0].baseline, Some(BaselineState::Existing));
+        assert_eq!(report.findings[1].baseline, Some(BaselineState::New));
+        assert_eq!(report.status, ScanStatus::Warn);
+    }
+
+    #[test]
+    fn fully_baselined_report_passes() {
+        let mut report = ScanReport::new(TargetKind::Web);
+        report.push(finding(
+            "web.headers.csp.missing",
+            Severity::High,
+            "nginx.conf",
+        ));
+        let baseline = Baseline::from_report(&report);
+
+        report.apply_baseline(&baseline);
+
+        assert_eq!(report.status, ScanStatus::Pass);
+        assert_eq!(report.policy.status, ScanStatus::Pass);
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::Finding;
+
+pub const RESULT_CACHE_VERSION: u32 = 1;
+
+/// On-disk form of a `ResultCache`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResultCacheFile {
+    pub version: u32,
+    /// Fingerprint of everything besides file contents that findings depend
+    /// on (binary, policy, rule catalog); a different key discards the cache.
+    pub key: String,
+    pub entries: Vec<CachedResult>,
+}
+
+/// Findings of one file-local check on one file, valid while the file
+/// content hashes to `content_hash`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CachedResult {
+    pub check: String,
+    /// Relative to the scan path.
+    pub file: PathBuf,
+    pub content_hash: String,
+    pub findings: Vec<Finding>,
+}
+
+pub(crate) type CheckEntries = BTreeMap<PathBuf, (String, Vec<Finding>)>;
+
+/// Results of file-local checks (`Check::is_file_local`) from earlier scans,
+/// shared by the checks of a scan through `ScanRequest::with_cache`. Files
+/// whose contents did not change are not handed to the check again.
+#[derive(Debug, Default)]
+pub struct ResultCache {
+    key: String,
+    entries: Mutex<HashMap<String, CheckEntries>>,
+}
+
+impl ResultCache {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            entries: Mutex::default(),
+        }
+    }
+
+    /// Loads `file`, or starts empty when it was written by another cache
+    /// version or under another key.
+    pub fn from_file(file: ResultCacheFile, key: impl Into<String>) -> Self {
+        let cache = Self::new(key);
+        if file.version != RESULT_CACHE_VERSION || file.key != cache.key {
+            return cache;
+        }
+        {
+            let mut entries = cache.lock();
+            for entry in file.entries {
+                entries
+                    .entry(entry.check)
+                    .or_default()
+                    .insert(entry.file, (entry.content_hash, entry.findings));
+            }
+        }
+        cache
+    }
+
+    pub fn to_file(&self) -> ResultCacheFile {
+        let entries = self.lock();
+        let mut checks: Vec<_> = entries.iter().collect();
+        checks.sort_by_key(|(check, _)| check.as_str());
+        ResultCacheFile {
+            version: RESULT_CACHE_VERSION,
+            key: self.key.clone(),
+            entries: checks
+                .into_iter()
+                .flat_map(|(check, files)| {
+                    files
+                        .iter()
+                        .map(|(file, (content_hash, findings))| CachedResult {
+                            check: check.clone(),
+                            file: file.clone(),
+                            content_hash: content_hash.clone(),
+                            findings: findings.clone(),
+                        })
+                })
+                .collect(),
+        }
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().values().map(BTreeMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cached files of `check` with the content hash they were evaluated at.
+    pub(crate) fn files_of(&self, check: &str) -> CheckEntries {
+        self.lock().get(check).cloned().unwrap_or_default()
+    }
+
+    /// Replaces every entry of `check`, dropping files that were deleted or
+    /// are no longer read.
+    pub(crate) fn replace(&self, check: &str, files: CheckEntries) {
+        self.lock().insert(check.to_string(), files);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, CheckEntries>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
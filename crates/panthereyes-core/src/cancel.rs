@@ -0,0 +1,89 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::EngineError;
+
+/// Stops a scan from another thread or after a deadline. Cancellation is
+/// cooperative: the workspace walk and `CheckContext::read_to_string` poll the
+/// token, and long-running checks should call `CheckContext::check_cancelled`.
+///
+/// Clones share the cancel flag; `child` tokens add their own deadline.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Token that expires `timeout` from now.
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self {
+            cancelled: Arc::default(),
+            deadline: Some(Instant::now() + timeout),
+        }
+    }
+
+    /// Token sharing this one's cancel flag whose deadline is the earlier of
+    /// this one's and `budget` from now.
+    pub fn child(&self, budget: Option<Duration>) -> Self {
+        let budget = budget.map(|budget| Instant::now() + budget);
+        Self {
+            cancelled: Arc::clone(&self.cancelled),
+            deadline: match (self.deadline, budget) {
+                (Some(deadline), Some(budget)) => Some(deadline.min(budget)),
+                (deadline, budget) => deadline.or(budget),
+            },
+        }
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// `cancel` was called on this token or a clone.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// The deadline has passed.
+    pub fn is_expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    pub fn should_stop(&self) -> bool {
+        self.is_cancelled() || self.is_expired()
+    }
+
+    /// `Err(EngineError::Cancelled)` once the scan should stop.
+    pub fn check(&self) -> Result<(), EngineError> {
+        if self.should_stop() {
+            return Err(EngineError::Cancelled);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn children_share_the_flag_and_take_the_earliest_deadline() {
+        let parent = CancellationToken::with_timeout(Duration::from_secs(60));
+        let child = parent.child(Some(Duration::ZERO));
+        assert!(child.is_expired() && !child.is_cancelled());
+        assert!(!parent.should_stop());
+
+        let unbounded = parent.child(None);
+        assert!(!unbounded.should_stop());
+        parent.cancel();
+        assert!(unbounded.is_cancelled());
+        assert!(matches!(unbounded.check(), Err(EngineError::Cancelled)));
+    }
+}
@@ -0,0 +1,986 @@
+use std::collections::{BTreeMap, HashSet};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Instant, SystemTime};
+
+use crate::cache::CheckEntries;
+use crate::metadata::micros;
+use crate::suppression::Suppressions;
+use crate::{
+    CancellationToken, CheckDiagnostic, CheckRun, CheckRunStatus, EngineError, Finding,
+    ResultCache, ScanMetadata, ScanReport, ScanRequest, Severity, TargetKind, Workspace,
+    WorkspaceFile,
+};
+
+/// Static description of a check: the finding id it emits and the catalog
+/// rule (`.panthereyes/rules.yaml`) it implements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CheckMetadata {
+    pub id: &'static str,
+    pub rule_id: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// Target reported on findings; `Ios`/`Android` checks run in the mobile engine.
+    pub target: TargetKind,
+    pub default_severity: Severity,
+    pub tags: &'static [&'static str],
+    pub allow_exception: bool,
+}
+
+impl CheckMetadata {
+    /// Catalogs may reference a check either by its catalog rule id or directly
+    /// by the finding id it emits.
+    pub fn implements(&self, rule_id: &str) -> bool {
+        self.rule_id == rule_id || self.id == rule_id
+    }
+}
+
+impl Finding {
+    /// Finding pre-filled from the check's metadata (id, title, severity, target, rule).
+    pub fn for_check(
+        check: &CheckMetadata,
+        message: impl Into<String>,
+        remediation: impl Into<String>,
+    ) -> Self {
+        Self::new(
+            check.id,
+            check.title,
+            check.default_severity,
+            check.target,
+            message,
+            remediation,
+        )
+        .with_rule(check.rule_id)
+    }
+}
+
+/// A single security check run by an engine over the scan workspace.
+///
+/// Implement it in any crate and hand it to an engine (`with_check`) or to a
+/// `CheckRegistry` to ship checks without forking the built-in crates.
+pub trait Check: Send + Sync {
+    fn metadata(&self) -> &CheckMetadata;
+    fn run(&self, ctx: &CheckContext<'_>, report: &mut ScanReport) -> Result<(), EngineError>;
+
+    /// Whether each finding depends only on the file it is reported on and
+    /// every finding has a file. Such checks are served from the result cache
+    /// for unchanged files and only see the other ones.
+    fn is_file_local(&self) -> bool {
+        false
+    }
+}
+
+/// What a check can see of the scan in progress.
+pub struct CheckContext<'a> {
+    request: &'a ScanRequest,
+    workspace: &'a Workspace,
+    /// Check the context was handed to, reported in `file_visited` events.
+    check: Option<&'a CheckMetadata>,
+    cancellation: CancellationToken,
+    /// Files read so far, by relative path.
+    reads: Mutex<BTreeMap<PathBuf, WorkspaceFile>>,
+    /// Files `read_file` could not read.
+    failed_reads: Mutex<Vec<CheckDiagnostic>>,
+}
+
+impl<'a> CheckContext<'a> {
+    pub fn new(request: &'a ScanRequest, workspace: &'a Workspace) -> Self {
+        Self {
+            request,
+            workspace,
+            check: None,
+            cancellation: request.cancellation().clone(),
+            reads: Mutex::default(),
+            failed_reads: Mutex::default(),
+        }
+    }
+
+    /// Context of one check run, whose token also expires after the request's
+    /// `check_budget`.
+    fn for_check(
+        request: &'a ScanRequest,
+        workspace: &'a Workspace,
+        check: &'a CheckMetadata,
+    ) -> Self {
+        Self {
+            check: Some(check),
+            cancellation: request.cancellation().child(request.check_budget),
+            ..Self::new(request, workspace)
+        }
+    }
+
+    pub fn request(&self) -> &'a ScanRequest {
+        self.request
+    }
+
+    pub fn workspace(&self) -> &'a Workspace {
+        self.workspace
+    }
+
+    pub fn files(&self) -> &'a [WorkspaceFile] {
+        self.workspace.files()
+    }
+
+    pub fn find_by_name(&self, file_name: &'a str) -> impl Iterator<Item = &'a WorkspaceFile> {
+        self.workspace.find_by_name(file_name)
+    }
+
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// `Err(EngineError::Cancelled)` once the check should stop; call it in
+    /// loops that do not go through `read_to_string`. Findings pushed before
+    /// are kept.
+    pub fn check_cancelled(&self) -> Result<(), EngineError> {
+        self.cancellation.check()
+    }
+
+    /// Cached contents; see `Workspace::read_to_string`. Fails with
+    /// `EngineError::Cancelled` once the check should stop.
+    pub fn read_to_string(&self, file: &WorkspaceFile) -> Result<Arc<str>, EngineError> {
+        self.check_cancelled()?;
+        self.lock_reads()
+            .entry(file.relative.clone())
+            .or_insert_with(|| file.clone());
+        if let Some(check) = self.check {
+            self.request.observer().file_visited(check, file);
+        }
+        self.workspace.read_to_string(file)
+    }
+
+    /// Like `read_to_string`, but a file that cannot be read only costs that
+    /// file: the failure is recorded as a diagnostic of the check and `None`
+    /// is returned so the check moves on to its next file. Cancellation still
+    /// stops the check.
+    pub fn read_file(&self, file: &WorkspaceFile) -> Result<Option<Arc<str>>, EngineError> {
+        match self.read_to_string(file) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(EngineError::Cancelled) => Err(EngineError::Cancelled),
+            Err(error) => {
+                let check = self.check.map_or("", |check| check.id);
+                let mut diagnostic = CheckDiagnostic::from_error(check, &error);
+                diagnostic.path = Some(file.relative.clone());
+                self.lock_failed_reads().push(diagnostic);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Diagnostics of the files `read_file` could not read so far.
+    pub fn failed_reads(&self) -> Vec<CheckDiagnostic> {
+        self.lock_failed_reads().clone()
+    }
+
+    /// Distinct files read through this context so far.
+    pub fn files_read(&self) -> usize {
+        self.lock_reads().len()
+    }
+
+    fn lock_reads(&self) -> std::sync::MutexGuard<'_, BTreeMap<PathBuf, WorkspaceFile>> {
+        self.reads
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_failed_reads(&self) -> std::sync::MutexGuard<'_, Vec<CheckDiagnostic>> {
+        self.failed_reads
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Runs the enabled `checks` over the request's workspace and returns the
+/// deduplicated report. Shared by the engines so they only differ in their checks.
+pub fn run_checks(
+    target: TargetKind,
+    checks: &[Arc<dyn Check>],
+    request: &ScanRequest,
+) -> Result<ScanReport, EngineError> {
+    let workspace = request.discover_workspace()?;
+    run_workspace_checks(target, checks, request, &workspace)
+}
+
+/// Same as `run_checks` over an already walked workspace. Checks run on up to
+/// `request.jobs()` threads; each fills its own report and the reports are
+/// merged in check order, so findings come out in the same order whatever the
+/// scheduling. The report's `metadata` records the timing of every check, and
+/// `request.observer()` is told about each check and finding as they happen.
+///
+/// Findings covered by a `panthereyes-ignore` comment naming their check are
+/// moved to `suppressed`; malformed and unused comments become warnings.
+pub fn run_workspace_checks(
+    target: TargetKind,
+    checks: &[Arc<dyn Check>],
+    request: &ScanRequest,
+    workspace: &Workspace,
+) -> Result<ScanReport, EngineError> {
+    let started_at = SystemTime::now();
+    let observer = request.observer();
+    let mut report = ScanReport::new(target);
+    report.warnings.extend(workspace.warnings());
+
+    let enabled = checks
+        .iter()
+        .filter(|check| request.is_check_enabled(check.metadata()))
+        .count();
+    observer.scan_started(target, workspace.files().len(), enabled);
+    let emitted = Mutex::new(HashSet::new());
+    let suppressions = Suppressions::new(workspace);
+    let partials = run_concurrently(checks, request.jobs(), |check| {
+        let metadata = check.metadata();
+        if !request.is_check_enabled(metadata) {
+            return (ScanReport::new(target), CheckRun::disabled(metadata));
+        }
+        if request.cancellation().should_stop() {
+            let run = CheckRun::not_run(metadata, interruption(request.cancellation()));
+            observer.check_finished(&run);
+            return (ScanReport::new(target), run);
+        }
+
+        observer.check_started(metadata);
+        let (mut partial, run, read) = match request.cache().filter(|_| check.is_file_local()) {
+            Some(cache) => run_cached(check.as_ref(), cache, request, workspace, target),
+            None => run_isolated(
+                check.as_ref(),
+                &CheckContext::for_check(request, workspace, metadata),
+                target,
+            ),
+        };
+        suppressions.apply(
+            metadata,
+            &read,
+            run.status == CheckRunStatus::Completed,
+            &mut partial,
+        );
+        {
+            let mut emitted = emitted.lock().unwrap_or_else(|poison| poison.into_inner());
+            for finding in &partial.findings {
+                if emitted.insert(finding.fingerprint.clone()) {
+                    observer.finding_emitted(finding);
+                }
+            }
+        }
+        observer.check_finished(&run);
+        (partial, run)
+    });
+    let mut metadata = ScanMetadata::new(started_at, SystemTime::now());
+    metadata.files_scanned = workspace.files().len();
+    metadata.files_skipped = workspace.skipped().len();
+    for (partial, run) in partials {
+        report.findings.extend(partial.findings);
+        report.suppressed.extend(partial.suppressed);
+        report.warnings.extend(partial.warnings);
+        report.diagnostics.extend(partial.diagnostics);
+        if run.status.is_unfinished() {
+            report.unfinished_checks.push(run.id.clone());
+        }
+        metadata.checks.push(run);
+    }
+    report.warnings.extend(suppressions.warnings());
+    report.incomplete = !report.unfinished_checks.is_empty() || !workspace.is_complete();
+
+    report.dedupe_findings();
+    report.metadata = Some(metadata);
+    observer.scan_finished(&report);
+    Ok(report)
+}
+
+/// Runs one check so that an error or a panic only costs that check: it is
+/// recorded as a diagnostic and the findings reported so far are kept. Also
+/// returns the files the check read.
+fn run_isolated(
+    check: &dyn Check,
+    ctx: &CheckContext<'_>,
+    target: TargetKind,
+) -> (ScanReport, CheckRun, Vec<PathBuf>) {
+    let mut partial = ScanReport::new(target);
+    let metadata = check.metadata();
+    let id = metadata.id;
+    let started = Instant::now();
+    let mut status = CheckRunStatus::Completed;
+    let diagnostic = match panic::catch_unwind(AssertUnwindSafe(|| check.run(ctx, &mut partial))) {
+        Ok(Ok(())) => None,
+        Ok(Err(EngineError::Cancelled)) => {
+            status = interruption(ctx.cancellation());
+            None
+        }
+        Ok(Err(error)) => {
+            let mut diagnostic = CheckDiagnostic::from_error(id, &error);
+            diagnostic.path = diagnostic
+                .path
+                .map(|path| ctx.workspace().relative_path(&path));
+            Some(diagnostic)
+        }
+        Err(payload) => Some(CheckDiagnostic::from_panic(id, payload.as_ref())),
+    };
+    let run = CheckRun {
+        id: id.to_string(),
+        rule_id: metadata.rule_id.to_string(),
+        status: match diagnostic {
+            Some(_) => CheckRunStatus::Failed,
+            None => status,
+        },
+        duration_us: micros(started.elapsed()),
+        files_considered: ctx.files_read(),
+        files_cached: 0,
+        files_matched: files_matched(&partial.findings),
+    };
+    partial.diagnostics.extend(ctx.failed_reads());
+    partial.diagnostics.extend(diagnostic);
+    let read = ctx.lock_reads().keys().cloned().collect();
+    (partial, run, read)
+}
+
+/// Runs a file-local check over the files whose contents changed since they
+/// were cached, reusing the cached findings of the others, and records the
+/// fresh results when the check completes.
+fn run_cached(
+    check: &dyn Check,
+    cache: &ResultCache,
+    request: &ScanRequest,
+    workspace: &Workspace,
+    target: TargetKind,
+) -> (ScanReport, CheckRun, Vec<PathBuf>) {
+    let metadata = check.metadata();
+    let mut cached = cache.files_of(metadata.id);
+    let mut reused = CheckEntries::new();
+    for file in workspace.files() {
+        if let Some((hash, findings)) = cached.remove(&file.relative) {
+            if workspace
+                .content_hash(file)
+                .is_ok_and(|current| current == hash)
+            {
+                reused.insert(file.relative.clone(), (hash, findings));
+            }
+        }
+    }
+
+    let unchanged: HashSet<&Path> = reused.keys().map(PathBuf::as_path).collect();
+    let view = workspace.without(&unchanged);
+    let ctx = CheckContext::for_check(request, &view, metadata);
+    let (mut partial, mut run, mut read) = run_isolated(check, &ctx, target);
+    if run.status == CheckRunStatus::Completed {
+        if let Some(fresh) = fresh_results(&ctx, &partial.findings) {
+            let mut entries = reused.clone();
+            entries.extend(fresh);
+            cache.replace(metadata.id, entries);
+        }
+    }
+
+    run.files_cached = reused.len();
+    run.files_considered += reused.len();
+    read.extend(reused.keys().cloned());
+    partial
+        .findings
+        .extend(reused.into_values().flat_map(|(_, findings)| findings));
+    // Same order as an uncached run, which visits files in path order.
+    partial
+        .findings
+        .sort_by(|left, right| left.file.cmp(&right.file));
+    run.files_matched = files_matched(&partial.findings);
+    (partial, run, read)
+}
+
+/// Findings of a completed run by file read, or `None` when a finding cannot
+/// be tied to one of them. Files that failed to read are left out, so they
+/// are read, and reported, again on the next scan.
+fn fresh_results(ctx: &CheckContext<'_>, findings: &[Finding]) -> Option<CheckEntries> {
+    let failed: HashSet<PathBuf> = ctx
+        .lock_failed_reads()
+        .iter()
+        .filter_map(|diagnostic| diagnostic.path.clone())
+        .collect();
+    let mut entries = CheckEntries::new();
+    for (relative, file) in ctx.lock_reads().iter() {
+        if failed.contains(relative) {
+            continue;
+        }
+        let hash = ctx.workspace().content_hash(file).ok()?;
+        entries.insert(relative.clone(), (hash, Vec::new()));
+    }
+    for finding in findings {
+        let (_, file_findings) = entries.get_mut(finding.file.as_ref()?)?;
+        file_findings.push(finding.clone());
+    }
+    Some(entries)
+}
+
+fn files_matched(findings: &[Finding]) -> usize {
+    findings
+        .iter()
+        .filter_map(|finding| finding.file.as_ref())
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Why a check stopped: an explicit cancel, or a deadline.
+fn interruption(cancellation: &CancellationToken) -> CheckRunStatus {
+    if cancellation.is_cancelled() {
+        CheckRunStatus::Cancelled
+    } else {
+        CheckRunStatus::TimedOut
+    }
+}
+
+/// Maps `items` on up to `jobs` scoped threads, returning results in item order.
+fn run_concurrently<T, R>(items: &[T], jobs: usize, run: impl Fn(&T) -> R + Sync) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let jobs = jobs.clamp(1, items.len().max(1));
+    if jobs == 1 {
+        return items.iter().map(run).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+                        done.push((index, run(item)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|payload| panic::resume_unwind(payload))
+            })
+            .collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Extra checks contributed by other crates, added to the engines of a custom
+/// `panthereyes` build next to the built-in ones.
+#[derive(Clone, Default)]
+pub struct CheckRegistry {
+    checks: Vec<Arc<dyn Check>>,
+}
+
+impl CheckRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, check: impl Check + 'static) -> &mut Self {
+        self.checks.push(Arc::new(check));
+        self
+    }
+
+    pub fn with_check(mut self, check: impl Check + 'static) -> Self {
+        self.register(check);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.checks.is_empty()
+    }
+
+    pub fn checks(&self) -> &[Arc<dyn Check>] {
+        &self.checks
+    }
+
+    /// Registered checks that belong to the engine scanning `target`.
+    pub fn checks_for(&self, target: TargetKind) -> impl Iterator<Item = &Arc<dyn Check>> {
+        let target = target.engine_target();
+        self.checks
+            .iter()
+            .filter(move |check| check.metadata().target.engine_target() == target)
+    }
+
+    pub fn metadata(&self) -> Vec<CheckMetadata> {
+        self.checks.iter().map(|check| *check.metadata()).collect()
+    }
+}
+
+impl std::fmt::Debug for CheckRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.checks.iter().map(|check| check.metadata().id))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use crate::DiagnosticKind;
+
+    struct TodoCommentCheck;
+
+    const TODO_COMMENT: CheckMetadata = CheckMetadata {
+        id: "custom.todo-comment",
+        rule_id: "custom.todo-comment",
+        title: "TODO em configuracao",
+        description: "Configuracoes nao devem carregar TODOs pendentes.",
+        target: TargetKind::Android,
+        default_severity: Severity::Low,
+        tags: &["custom"],
+        allow_exception: true,
+    };
+
+    impl Check for TodoCommentCheck {
+        fn metadata(&self) -> &CheckMetadata {
+            &TODO_COMMENT
+        }
+
+        fn run(&self, ctx: &CheckContext<'_>, report: &mut ScanReport) -> Result<(), EngineError> {
+            for file in ctx.files() {
+                let Some(raw) = ctx.read_file(file)? else {
+                    continue;
+                };
+                if let Some(start) = raw.find("TODO") {
+                    report.push(
+                        Finding::for_check(self.metadata(), "TODO encontrado.", "Resolva o TODO.")
+                            .with_file(&file.relative)
+                            .with_match(&raw, start..start + 4),
+                    );
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn registry_routes_checks_to_engine_targets_and_runs_them() {
+        let registry = CheckRegistry::new().with_check(TodoCommentCheck);
+        assert_eq!(registry.checks_for(TargetKind::Mobile).count(), 1);
+        assert_eq!(registry.checks_for(TargetKind::Web).count(), 0);
+        assert_eq!(registry.metadata(), [TODO_COMMENT]);
+
+        let root = TempDir::new("panthereyes-core-registry");
+        root.write("app.conf", "# TODO remove\n");
+
+        let request = ScanRequest::new(TargetKind::Mobile, root.path());
+        let report =
+            run_checks(TargetKind::Mobile, registry.checks(), &request).expect("run checks");
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(
+            report.findings[0].rule_id.as_deref(),
+            Some("custom.todo-comment")
+        );
+
+        let metadata = report.metadata.expect("metadata");
+        assert_eq!(metadata.files_scanned, 1);
+        assert_eq!(metadata.checks.len(), 1);
+        let run = &metadata.checks[0];
+        assert_eq!(run.status, CheckRunStatus::Completed);
+        assert_eq!((run.files_considered, run.files_matched), (1, 1));
+
+        let disabled = request.with_disabled_checks(["custom.todo-comment"]);
+        let report =
+            run_checks(TargetKind::Mobile, registry.checks(), &disabled).expect("run checks");
+        assert!(report.findings.is_empty());
+        assert_eq!(
+            report.metadata.expect("metadata").checks,
+            [CheckRun::disabled(&TODO_COMMENT)]
+        );
+    }
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl RecordingObserver {
+        fn record(&self, event: String) {
+            self.events.lock().expect("events").push(event);
+        }
+    }
+
+    impl crate::ScanObserver for RecordingObserver {
+        fn scan_started(&self, target: TargetKind, files: usize, checks: usize) {
+            self.record(format!("start {} {files} {checks}", target.as_str()));
+        }
+
+        fn check_started(&self, check: &CheckMetadata) {
+            self.record(format!("check {}", check.id));
+        }
+
+        fn file_visited(&self, _check: &CheckMetadata, file: &WorkspaceFile) {
+            self.record(format!("file {}", file.relative.display()));
+        }
+
+        fn finding_emitted(&self, finding: &Finding) {
+            self.record(format!("finding {}", finding.id));
+        }
+
+        fn check_finished(&self, run: &CheckRun) {
+            self.record(format!("done {}", run.id));
+        }
+
+        fn scan_finished(&self, report: &ScanReport) {
+            self.record(format!("end {}", report.findings.len()));
+        }
+    }
+
+    #[test]
+    fn observer_sees_checks_files_and_findings() {
+        let root = TempDir::new("panthereyes-core-observer");
+        root.write("app.conf", "# TODO remove\n");
+
+        let observer = Arc::new(RecordingObserver::default());
+        let request = ScanRequest::new(TargetKind::Mobile, root.path())
+            .with_jobs(1)
+            .with_observer(observer.clone());
+        let checks = [Arc::new(TodoCommentCheck) as Arc<dyn Check>];
+        run_checks(TargetKind::Mobile, &checks, &request).expect("run checks");
+
+        assert_eq!(
+            *observer.events.lock().expect("events"),
+            [
+                "start mobile 1 1",
+                "check custom.todo-comment",
+                "file app.conf",
+                "finding custom.todo-comment",
+                "done custom.todo-comment",
+                "end 1",
+            ]
+        );
+    }
+
+    struct LocalTodoCheck;
+
+    impl Check for LocalTodoCheck {
+        fn metadata(&self) -> &CheckMetadata {
+            TodoCommentCheck.metadata()
+        }
+
+        fn run(&self, ctx: &CheckContext<'_>, report: &mut ScanReport) -> Result<(), EngineError> {
+            TodoCommentCheck.run(ctx, report)
+        }
+
+        fn is_file_local(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn result_cache_reuses_findings_of_unchanged_files() {
+        let root = TempDir::new("panthereyes-core-cache");
+        root.write("a.conf", "# TODO a\n");
+        root.write("b.conf", "# TODO b\n");
+        let checks = [Arc::new(LocalTodoCheck) as Arc<dyn Check>];
+        let cache = Arc::new(ResultCache::new("key"));
+        let request =
+            ScanRequest::new(TargetKind::Mobile, root.path()).with_cache(Arc::clone(&cache));
+
+        let first = run_checks(TargetKind::Mobile, &checks, &request).expect("scan");
+        assert_eq!(cache.len(), 2);
+
+        root.write("b.conf", "\n# TODO b\n");
+        let second = run_checks(TargetKind::Mobile, &checks, &request).expect("scan");
+        let run = &second.metadata.as_ref().expect("metadata").checks[0];
+        assert_eq!((run.files_cached, run.files_considered), (1, 2));
+        assert_eq!(second.findings[0], first.findings[0]);
+        assert_eq!(
+            second.findings[1].span.map(|span| span.line),
+            Some(2),
+            "b.conf was evaluated again"
+        );
+
+        let restored = ResultCache::from_file(cache.to_file(), "key");
+        assert_eq!(restored.to_file(), cache.to_file());
+        assert!(ResultCache::from_file(cache.to_file(), "other binary").is_empty());
+    }
+
+    #[test]
+    fn inline_comments_suppress_findings_and_unused_ones_are_reported() {
+        let root = TempDir::new("panthereyes-core-suppression");
+        let ignore = "# panthereyes-ignore custom.todo-comment";
+        root.write("a.conf", format!("{ignore}: revisado\n# TODO a\n"));
+        root.write(
+            "b.conf",
+            format!("{ignore}: revisado\nlisten 80;\n# TODO b\n"),
+        );
+        root.write("c.conf", format!("{ignore}\n# TODO c\n"));
+        root.write(
+            "d.conf",
+            "# panthereyes-ignore web.hsts.required: outro motor\n",
+        );
+        let checks = [Arc::new(LocalTodoCheck) as Arc<dyn Check>];
+        let cache = Arc::new(ResultCache::new("key"));
+        let request = ScanRequest::new(TargetKind::Mobile, root.path()).with_cache(cache);
+
+        let report = run_checks(TargetKind::Mobile, &checks, &request).expect("scan");
+        let files = |findings: &[Finding]| -> Vec<_> {
+            findings
+                .iter()
+                .filter_map(|finding| finding.file.clone())
+                .collect()
+        };
+        assert_eq!(
+            files(&report.findings),
+            [PathBuf::from("b.conf"), PathBuf::from("c.conf")]
+        );
+        assert_eq!(files(&report.suppressed), [PathBuf::from("a.conf")]);
+        let suppression = report.suppressed[0].suppression.as_ref().expect("comment");
+        assert_eq!(
+            (suppression.line, suppression.reason.as_str()),
+            (1, "revisado")
+        );
+        let warnings: Vec<_> = report
+            .warnings
+            .iter()
+            .map(|warning| (warning.code.as_str(), warning.subject.as_deref()))
+            .collect();
+        assert_eq!(
+            warnings,
+            [
+                ("suppression.unused", Some("b.conf:1")),
+                ("suppression.invalid", Some("c.conf:1")),
+            ]
+        );
+
+        let cached = run_checks(TargetKind::Mobile, &checks, &request).expect("scan");
+        assert_eq!(
+            cached.metadata.as_ref().expect("metadata").checks[0].files_cached,
+            4
+        );
+        assert_eq!(
+            (cached.findings, cached.suppressed, cached.warnings),
+            (report.findings, report.suppressed, report.warnings)
+        );
+    }
+
+    struct SlowCheck {
+        metadata: CheckMetadata,
+        delay_ms: u64,
+    }
+
+    impl Check for SlowCheck {
+        fn metadata(&self) -> &CheckMetadata {
+            &self.metadata
+        }
+
+        fn run(&self, ctx: &CheckContext<'_>, report: &mut ScanReport) -> Result<(), EngineError> {
+            std::thread::sleep(std::time::Duration::from_millis(self.delay_ms));
+            for file in ctx.files() {
+                ctx.read_to_string(file)?;
+                report.push(Finding::for_check(&self.metadata, "m", "r").with_file(&file.relative));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn parallel_runs_keep_check_order_and_share_file_contents() {
+        const IDS: [&str; 4] = ["slow.a", "slow.b", "slow.c", "slow.d"];
+        let checks: Vec<Arc<dyn Check>> = IDS
+            .iter()
+            .enumerate()
+            .map(|(index, id)| {
+                Arc::new(SlowCheck {
+                    metadata: CheckMetadata {
+                        id,
+                        rule_id: id,
+                        ..TODO_COMMENT
+                    },
+                    delay_ms: 40 - 10 * index as u64,
+                }) as Arc<dyn Check>
+            })
+            .collect();
+
+        let root = TempDir::new("panthereyes-core-parallel");
+        root.write("a.conf", "a");
+        root.write("b.conf", "b");
+
+        let request = ScanRequest::new(TargetKind::Mobile, root.path()).with_jobs(4);
+        let workspace = request.discover_workspace().expect("walk");
+        let report = run_workspace_checks(TargetKind::Mobile, &checks, &request, &workspace)
+            .expect("run checks");
+        let order: Vec<_> = report
+            .findings
+            .iter()
+            .map(|finding| (finding.id.as_str(), finding.file.clone().unwrap()))
+            .collect();
+        let expected: Vec<_> = IDS
+            .iter()
+            .flat_map(|id| ["a.conf", "b.conf"].map(|file| (*id, file.into())))
+            .collect();
+        assert_eq!(order, expected);
+
+        // Contents were read once and are served from the cache from now on.
+        root.write("a.conf", "changed");
+        let cached = workspace
+            .read_to_string(&workspace.files()[0])
+            .expect("read");
+        assert_eq!(&*cached, "a");
+    }
+
+    struct SpinningCheck;
+
+    const SPINNING: CheckMetadata = CheckMetadata {
+        id: "custom.spinning",
+        rule_id: "custom.spinning",
+        ..TODO_COMMENT
+    };
+
+    impl Check for SpinningCheck {
+        fn metadata(&self) -> &CheckMetadata {
+            &SPINNING
+        }
+
+        fn run(&self, ctx: &CheckContext<'_>, _report: &mut ScanReport) -> Result<(), EngineError> {
+            loop {
+                ctx.check_cancelled()?;
+                std::thread::sleep(std::time::Duration::from_millis(5));
+            }
+        }
+    }
+
+    #[test]
+    fn budgets_and_cancellation_stop_checks_and_mark_the_report_incomplete() {
+        let root = TempDir::new("panthereyes-core-cancel");
+        root.write("app.conf", "# TODO remove\n");
+        let checks = [
+            Arc::new(SpinningCheck) as Arc<dyn Check>,
+            Arc::new(TodoCommentCheck) as Arc<dyn Check>,
+        ];
+
+        let request = ScanRequest::new(TargetKind::Mobile, root.path())
+            .with_jobs(2)
+            .with_check_budget(std::time::Duration::from_millis(20));
+        let report = run_checks(TargetKind::Mobile, &checks, &request).expect("scan");
+        assert!(report.incomplete);
+        assert_eq!(report.unfinished_checks, ["custom.spinning"]);
+        assert_eq!(
+            report.findings.len(),
+            1,
+            "finished checks keep their findings"
+        );
+        assert!(report.diagnostics.is_empty());
+        assert_eq!(report.status, crate::ScanStatus::Warn);
+        let statuses: Vec<_> = report
+            .metadata
+            .expect("metadata")
+            .checks
+            .iter()
+            .map(|run| run.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            [CheckRunStatus::TimedOut, CheckRunStatus::Completed]
+        );
+
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+        let request =
+            ScanRequest::new(TargetKind::Mobile, root.path()).with_cancellation(cancellation);
+        let report = run_checks(TargetKind::Mobile, &checks, &request).expect("scan");
+        assert!(report.incomplete);
+        assert!(report.findings.is_empty());
+        assert_eq!(
+            report.unfinished_checks,
+            ["custom.spinning", "custom.todo-comment"]
+        );
+        assert_eq!(
+            report.metadata.expect("metadata").checks[1].status,
+            CheckRunStatus::Cancelled
+        );
+    }
+
+    struct FailingCheck {
+        metadata: CheckMetadata,
+    }
+
+    impl Check for FailingCheck {
+        fn metadata(&self) -> &CheckMetadata {
+            &self.metadata
+        }
+
+        fn run(&self, ctx: &CheckContext<'_>, _report: &mut ScanReport) -> Result<(), EngineError> {
+            if self.metadata.id == "broken.panic" {
+                panic!("unexpected manifest layout");
+            }
+            for file in ctx.files() {
+                ctx.read_to_string(file)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn failing_and_panicking_checks_become_diagnostics() {
+        let failing = |id| {
+            Arc::new(FailingCheck {
+                metadata: CheckMetadata {
+                    id,
+                    rule_id: id,
+                    ..TODO_COMMENT
+                },
+            }) as Arc<dyn Check>
+        };
+        let checks = [
+            failing("broken.read"),
+            failing("broken.panic"),
+            Arc::new(TodoCommentCheck) as Arc<dyn Check>,
+        ];
+
+        let root = TempDir::new("panthereyes-core-diagnostics");
+        root.write("app.conf", b"# TODO caf\xe9\n");
+
+        let request = ScanRequest::new(TargetKind::Mobile, root.path()).with_jobs(2);
+        let report = run_checks(TargetKind::Mobile, &checks, &request).expect("scan continues");
+        let diagnostics: Vec<_> = report
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.check.as_str(), diagnostic.kind))
+            .collect();
+        assert_eq!(
+            diagnostics,
+            [
+                ("broken.read", DiagnosticKind::InvalidEncoding),
+                ("broken.panic", DiagnosticKind::Panic),
+                ("custom.todo-comment", DiagnosticKind::InvalidEncoding),
+            ]
+        );
+        assert_eq!(
+            report.diagnostics[0].path.as_deref(),
+            Some(std::path::Path::new("app.conf"))
+        );
+        assert_eq!(report.diagnostics[1].message, "unexpected manifest layout");
+        let statuses: Vec<_> = report
+            .metadata
+            .as_ref()
+            .expect("metadata")
+            .checks
+            .iter()
+            .map(|run| run.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                CheckRunStatus::Failed,
+                CheckRunStatus::Failed,
+                CheckRunStatus::Completed
+            ]
+        );
+        assert_eq!(report.status, crate::ScanStatus::Pass);
+
+        let gated = report.with_policy(crate::PolicyResult {
+            fail_on_diagnostics: true,
+            ..crate::PolicyResult::default()
+        });
+        assert_eq!(gated.status, crate::ScanStatus::Block);
+    }
+}
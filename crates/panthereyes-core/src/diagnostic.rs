@@ -0,0 +1,90 @@
+use std::any::Any;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use crate::EngineError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum DiagnosticKind {
+    Io,
+    PermissionDenied,
+    /// File is not valid UTF-8.
+    InvalidEncoding,
+    InvalidRequest,
+    /// Engine-level scan stopped by its `CancellationToken`.
+    Cancelled,
+    Panic,
+}
+
+impl DiagnosticKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Io => "io",
+            Self::PermissionDenied => "permission-denied",
+            Self::InvalidEncoding => "invalid-encoding",
+            Self::InvalidRequest => "invalid-request",
+            Self::Cancelled => "cancelled",
+            Self::Panic => "panic",
+        }
+    }
+
+    fn from_error(error: &EngineError) -> Self {
+        match error {
+            EngineError::InvalidRequest(_) => Self::InvalidRequest,
+            EngineError::Cancelled => Self::Cancelled,
+            EngineError::Io { source, .. } => match source.kind() {
+                ErrorKind::PermissionDenied => Self::PermissionDenied,
+                ErrorKind::InvalidData => Self::InvalidEncoding,
+                _ => Self::Io,
+            },
+        }
+    }
+}
+
+/// A check that failed or panicked. The scan goes on without it; policies
+/// decide with `failOnDiagnostics` whether that blocks the gate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CheckDiagnostic {
+    /// Id of the failing check (or engine name for engine-level failures).
+    pub check: String,
+    pub kind: DiagnosticKind,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub path: Option<PathBuf>,
+    pub message: String,
+}
+
+impl CheckDiagnostic {
+    pub fn from_error(check: impl Into<String>, error: &EngineError) -> Self {
+        let (path, message) = match error {
+            EngineError::InvalidRequest(message) => (None, message.clone()),
+            EngineError::Cancelled => (None, error.to_string()),
+            EngineError::Io { path, source } => (path.clone(), source.to_string()),
+        };
+        Self {
+            check: check.into(),
+            kind: DiagnosticKind::from_error(error),
+            path,
+            message,
+        }
+    }
+
+    pub fn from_panic(check: impl Into<String>, payload: &(dyn Any + Send)) -> Self {
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "check panicked".to_string());
+        Self {
+            check: check.into(),
+            kind: DiagnosticKind::Panic,
+            path: None,
+            message,
+        }
+    }
+}
@@ -1,8 +1,47 @@
+use std::collections::HashSet;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+mod baseline;
+mod cache;
+mod cancel;
+mod check;
+mod diagnostic;
+mod metadata;
+mod observer;
+mod orchestrator;
+mod score;
+mod suppression;
+#[cfg(test)]
+mod test_support;
+mod workspace;
+
+pub use baseline::{Baseline, BaselineEntry, BaselineState, BaselineSummary, BASELINE_VERSION};
+pub use cache::{CachedResult, ResultCache, ResultCacheFile, RESULT_CACHE_VERSION};
+pub use cancel::CancellationToken;
+pub use check::{
+    run_checks, run_workspace_checks, Check, CheckContext, CheckMetadata, CheckRegistry,
+};
+pub use diagnostic::{CheckDiagnostic, DiagnosticKind};
+pub use metadata::{CheckRun, CheckRunStatus, EngineMetadata, ScanMetadata};
+pub use observer::{NoopObserver, ScanObserver};
+pub use orchestrator::{CombinedReport, ScanOrchestrator, TargetSummary};
+pub use score::{ScoreWeights, MAX_SCORE};
+pub use suppression::{InlineSuppression, SUPPRESSION_MARKER};
+pub use workspace::{
+    SkipReason, SkippedEntry, Workspace, WorkspaceFile, WorkspaceOptions, DEFAULT_EXCLUDED_DIRS,
+    DEFAULT_MAX_FILE_SIZE, PANTHEREYES_IGNORE_FILE, RESULT_CACHE_DIR,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum TargetKind {
     Web,
     Mobile,
@@ -19,9 +58,19 @@ impl TargetKind {
             Self::Android => "android",
         }
     }
+
+    /// Target of the engine that scans this kind: platform kinds fold into `Mobile`.
+    pub fn engine_target(self) -> Self {
+        match self {
+            Self::Web => Self::Web,
+            Self::Mobile | Self::Ios | Self::Android => Self::Mobile,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Severity {
     Low,
     Medium,
@@ -40,7 +89,10 @@ impl Severity {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Ordered from least to most severe, so the worst of several statuses is their `max`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum ScanStatus {
     Pass,
     Warn,
@@ -57,15 +109,91 @@ impl ScanStatus {
     }
 }
 
+/// Gating mode configured per environment in `.panthereyes/policy.yaml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum PolicyMode {
+    Audit,
+    Warn,
+    Enforce,
+}
+
+impl PolicyMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Audit => "audit",
+            Self::Warn => "warn",
+            Self::Enforce => "enforce",
+        }
+    }
+
+    /// Final scan status for a severity-based evaluation: `audit` only records,
+    /// `warn` downgrades blocks to warnings and `enforce` keeps them.
+    pub fn gate(self, evaluated: ScanStatus) -> ScanStatus {
+        match (self, evaluated) {
+            (Self::Audit, _) => ScanStatus::Pass,
+            (Self::Warn, ScanStatus::Block) => ScanStatus::Warn,
+            (_, status) => status,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Finding {
     pub id: String,
+    /// Catalog rule (`rules.yaml`) implemented by the check that emitted this finding.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub rule_id: Option<String>,
     pub title: String,
     pub severity: Severity,
+    /// Severity emitted by the check before a policy `ruleOverrides` entry rewrote it.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub original_severity: Option<Severity>,
     pub target: TargetKind,
     pub file: Option<PathBuf>,
     pub message: String,
     pub remediation: String,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub span: Option<SourceSpan>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub evidence: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub fingerprint: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub baseline: Option<BaselineState>,
+    /// Id of the policy exception covering this finding, if any.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub exception: Option<String>,
+    /// Inline `panthereyes-ignore` comment covering this finding; set only on
+    /// the findings of `ScanReport::suppressed`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub suppression: Option<InlineSuppression>,
 }
 
 impl Finding {
@@ -79,12 +207,20 @@ impl Finding {
     ) -> Self {
         Self {
             id: id.into(),
+            rule_id: None,
             title: title.into(),
             severity,
+            original_severity: None,
             target,
             file: None,
             message: message.into(),
             remediation: remediation.into(),
+            span: None,
+            evidence: None,
+            fingerprint: None,
+            baseline: None,
+            exception: None,
+            suppression: None,
         }
     }
 
@@ -92,29 +228,284 @@ impl Finding {
         self.file = Some(file.into());
         self
     }
+
+    pub fn with_rule(mut self, rule_id: impl Into<String>) -> Self {
+        self.rule_id = Some(rule_id.into());
+        self
+    }
+
+    /// Whether `rule_id` refers to this finding, either by finding id or by catalog rule id.
+    pub fn matches_rule(&self, rule_id: &str) -> bool {
+        self.id == rule_id || self.rule_id.as_deref() == Some(rule_id)
+    }
+
+    pub fn with_span(mut self, span: SourceSpan) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    pub fn with_evidence(mut self, evidence: impl Into<String>) -> Self {
+        self.evidence = Some(evidence.into());
+        self
+    }
+
+    /// Records where `range` (byte offsets into `source`) matched, filling both
+    /// the span and an evidence excerpt built from the matched lines.
+    pub fn with_match(self, source: &str, range: Range<usize>) -> Self {
+        let span = SourceSpan::from_byte_range(source, range.clone());
+        self.with_span(span)
+            .with_evidence(evidence_excerpt(source, range))
+    }
+
+    /// Deterministic identity for this finding across scans.
+    ///
+    /// Hashes the rule id, the normalized relative path and the whitespace-collapsed
+    /// evidence (falling back to the message). Line numbers are deliberately left
+    /// out so the fingerprint survives unrelated edits that shift the match.
+    pub fn compute_fingerprint(&self) -> String {
+        let path = self
+            .file
+            .as_deref()
+            .map(normalize_fingerprint_path)
+            .unwrap_or_default();
+        let anchor = self.evidence.as_deref().unwrap_or(&self.message);
+        let anchor = anchor.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        let mut hasher = Sha256::new();
+        hasher.update(FINGERPRINT_VERSION.as_bytes());
+        for part in [self.id.as_str(), path.as_str(), anchor.as_str()] {
+            hasher.update([0]);
+            hasher.update(part.as_bytes());
+        }
+
+        hasher
+            .finalize()
+            .iter()
+            .take(FINGERPRINT_BYTES)
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+
+    /// Fills `fingerprint` unless one was already recorded (e.g. loaded from a saved report).
+    pub fn ensure_fingerprint(&mut self) -> &str {
+        if self.fingerprint.is_none() {
+            self.fingerprint = Some(self.compute_fingerprint());
+        }
+        self.fingerprint.as_deref().unwrap_or_default()
+    }
+
+    /// Rewrites the severity, keeping the check's own severity in `original_severity`.
+    pub fn override_severity(&mut self, severity: Severity) {
+        if severity == self.severity {
+            return;
+        }
+        self.original_severity.get_or_insert(self.severity);
+        self.severity = severity;
+    }
+
+    /// Whether this finding participates in status derivation. Findings already
+    /// accepted in a baseline or covered by an exception are reported but never
+    /// gate the scan.
+    pub fn counts_toward_status(&self) -> bool {
+        self.baseline != Some(BaselineState::Existing) && self.exception.is_none()
+    }
+
+    /// `file:line:col` when both are known, otherwise just the file.
+    pub fn location_label(&self) -> Option<String> {
+        let file = self.file.as_ref()?;
+        Some(match self.span {
+            Some(span) => format!("{}:{}:{}", file.display(), span.line, span.column),
+            None => file.display().to_string(),
+        })
+    }
+}
+
+/// 1-based line/column span inside a scanned file. The end position points
+/// just past the last matched character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SourceSpan {
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl SourceSpan {
+    pub fn from_byte_range(source: &str, range: Range<usize>) -> Self {
+        let (line, column) = line_column(source, range.start);
+        let (end_line, end_column) = line_column(source, range.end.max(range.start));
+        Self {
+            line,
+            column,
+            end_line,
+            end_column,
+        }
+    }
+}
+
+const EVIDENCE_MAX_CHARS: usize = 200;
+const FINGERPRINT_VERSION: &str = "panthereyes-fingerprint-v1";
+const FINGERPRINT_BYTES: usize = 16;
+
+fn normalize_fingerprint_path(path: &Path) -> String {
+    let raw = path.to_string_lossy().replace('\\', "/");
+    raw.trim_start_matches("./").to_string()
+}
+
+fn line_column(source: &str, offset: usize) -> (usize, usize) {
+    let offset = floor_char_boundary(source, offset);
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |pos| pos + 1);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+fn floor_char_boundary(source: &str, offset: usize) -> usize {
+    let mut offset = offset.min(source.len());
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+fn evidence_excerpt(source: &str, range: Range<usize>) -> String {
+    let start = floor_char_boundary(source, range.start);
+    let end = floor_char_boundary(source, range.end.max(range.start));
+    let line_start = source[..start].rfind('\n').map_or(0, |pos| pos + 1);
+    let line_end = source[end..]
+        .find('\n')
+        .map_or(source.len(), |pos| end + pos);
+
+    let excerpt = source[line_start..line_end]
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if excerpt.chars().count() > EVIDENCE_MAX_CHARS {
+        let truncated: String = excerpt.chars().take(EVIDENCE_MAX_CHARS).collect();
+        format!("{truncated}...")
+    } else {
+        excerpt
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PolicyResult {
+    /// Status from `blocking_severity` and `min_score`, before `mode` is applied.
     pub status: ScanStatus,
     pub blocking_severity: Severity,
+    pub mode: PolicyMode,
+    /// Scores below this block the scan (policy `minScore` directive).
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub min_score: Option<u8>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub score_weights: ScoreWeights,
+    /// Block when a check failed (policy `failOnDiagnostics` directive).
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub fail_on_diagnostics: bool,
 }
 
 impl Default for PolicyResult {
+    // Scans without a policy keep blocking on high findings.
     fn default() -> Self {
         Self {
             status: ScanStatus::Pass,
             blocking_severity: Severity::High,
+            mode: PolicyMode::Enforce,
+            min_score: None,
+            score_weights: ScoreWeights::default(),
+            fail_on_diagnostics: false,
         }
     }
 }
 
+/// Report-level notice that does not change the status on its own, such as an
+/// exception that expired or is about to.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScanWarning {
+    pub code: String,
+    pub message: String,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub subject: Option<String>,
+}
+
+impl ScanWarning {
+    pub fn new(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            code: code.into(),
+            message: message.into(),
+            subject: None,
+        }
+    }
+
+    pub fn with_subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScanReport {
     pub target: TargetKind,
-    pub findings: Vec<Finding>,
     pub status: ScanStatus,
+    /// 0-100 score from the gating findings, weighted by `policy.score_weights`.
+    pub score: u8,
     pub policy: PolicyResult,
+    pub findings: Vec<Finding>,
+    /// Findings silenced by an inline suppression comment; they never gate
+    /// the scan nor count toward the score.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub suppressed: Vec<Finding>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub baseline: Option<BaselineSummary>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub warnings: Vec<ScanWarning>,
+    /// Checks that failed or panicked; their findings may be missing.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub diagnostics: Vec<CheckDiagnostic>,
+    /// The scan was cancelled or ran out of time: the walk or some checks did
+    /// not finish and findings may be missing.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    pub incomplete: bool,
+    /// Ids of the checks that were stopped or never started.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub unfinished_checks: Vec<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub metadata: Option<ScanMetadata>,
 }
 
 impl ScanReport {
@@ -123,48 +514,115 @@ impl ScanReport {
         Self {
             target,
             findings: Vec::new(),
+            suppressed: Vec::new(),
             status: policy.status,
+            score: MAX_SCORE,
             policy,
+            baseline: None,
+            warnings: Vec::new(),
+            diagnostics: Vec::new(),
+            incomplete: false,
+            unfinished_checks: Vec::new(),
+            metadata: None,
         }
     }
 
     pub fn with_policy(mut self, policy: PolicyResult) -> Self {
         self.policy = policy;
-        self.status = derive_status(&self.findings, self.policy.blocking_severity);
+        self.recompute_status();
         self
     }
 
-    pub fn push(&mut self, finding: Finding) {
+    pub fn push(&mut self, mut finding: Finding) {
+        finding.ensure_fingerprint();
         self.findings.push(finding);
-        self.status = derive_status(&self.findings, self.policy.blocking_severity);
-        self.policy.status = self.status;
+        self.recompute_status();
+    }
+
+    /// Drops repeated hits that share a fingerprint, keeping the first occurrence.
+    pub fn dedupe_findings(&mut self) {
+        for findings in [&mut self.findings, &mut self.suppressed] {
+            let mut seen = HashSet::new();
+            findings.retain_mut(|finding| {
+                let fingerprint = finding.ensure_fingerprint().to_string();
+                seen.insert(fingerprint)
+            });
+        }
+        self.recompute_status();
+    }
+
+    pub fn fingerprints(&self) -> impl Iterator<Item = &str> {
+        self.findings
+            .iter()
+            .filter_map(|finding| finding.fingerprint.as_deref())
+    }
+
+    pub fn push_diagnostic(&mut self, diagnostic: CheckDiagnostic) {
+        self.diagnostics.push(diagnostic);
+        self.recompute_status();
+    }
+
+    /// Diagnostics gate the scan only when the policy says so.
+    pub fn fails_on_diagnostics(&self) -> bool {
+        self.policy.fail_on_diagnostics && !self.diagnostics.is_empty()
+    }
+
+    pub fn below_min_score(&self) -> bool {
+        self.policy
+            .min_score
+            .is_some_and(|min_score| self.score < min_score)
     }
 
     pub fn recompute_status(&mut self) {
-        self.status = derive_status(&self.findings, self.policy.blocking_severity);
-        self.policy.status = self.status;
+        self.score = self.policy.score_weights.score(
+            self.findings
+                .iter()
+                .filter(|finding| finding.counts_toward_status()),
+        );
+        self.policy.status = if self.below_min_score() || self.fails_on_diagnostics() {
+            ScanStatus::Block
+        } else {
+            derive_status(&self.findings, self.policy.blocking_severity)
+        };
+        // Missing results must not read as a clean pass.
+        if self.incomplete {
+            self.policy.status = self.policy.status.max(ScanStatus::Warn);
+        }
+        self.status = self.policy.mode.gate(self.policy.status);
     }
 }
 
 fn derive_status(findings: &[Finding], blocking_severity: Severity) -> ScanStatus {
-    if findings.is_empty() {
+    let mut gating = findings
+        .iter()
+        .filter(|finding| finding.counts_toward_status())
+        .peekable();
+    if gating.peek().is_none() {
         return ScanStatus::Pass;
     }
 
-    if findings
-        .iter()
-        .any(|finding| finding.severity >= blocking_severity)
-    {
+    if gating.any(|finding| finding.severity >= blocking_severity) {
         return ScanStatus::Block;
     }
 
     ScanStatus::Warn
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Clone)]
 pub struct ScanRequest {
     pub target: TargetKind,
     pub path: PathBuf,
+    /// Check or catalog rule ids that must not run (policy `enabled: false`).
+    pub disabled_checks: Vec<String>,
+    pub workspace: WorkspaceOptions,
+    /// Threads for walking the workspace and running checks; 0 uses the
+    /// available parallelism.
+    pub jobs: usize,
+    /// Time each check may run before it is stopped.
+    pub check_budget: Option<Duration>,
+    observer: Arc<dyn ScanObserver>,
+    cancellation: CancellationToken,
+    cache: Option<Arc<ResultCache>>,
 }
 
 impl ScanRequest {
@@ -172,14 +630,129 @@ impl ScanRequest {
         Self {
             target,
             path: path.into(),
+            disabled_checks: Vec::new(),
+            workspace: WorkspaceOptions::default(),
+            jobs: 0,
+            check_budget: None,
+            observer: Arc::new(NoopObserver),
+            cancellation: CancellationToken::new(),
+            cache: None,
+        }
+    }
+
+    pub fn with_workspace(mut self, workspace: WorkspaceOptions) -> Self {
+        self.workspace = workspace;
+        self
+    }
+
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    pub fn with_observer(mut self, observer: Arc<dyn ScanObserver>) -> Self {
+        self.observer = observer;
+        self
+    }
+
+    pub fn observer(&self) -> &dyn ScanObserver {
+        self.observer.as_ref()
+    }
+
+    /// Token that stops the walk and the checks; `CancellationToken::with_timeout`
+    /// bounds the whole scan.
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
+    /// Cache of file-local check results, read and updated by the scan.
+    pub fn with_cache(mut self, cache: Arc<ResultCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn cache(&self) -> Option<&ResultCache> {
+        self.cache.as_deref()
+    }
+
+    pub fn with_check_budget(mut self, budget: Duration) -> Self {
+        self.check_budget = Some(budget);
+        self
+    }
+
+    /// `jobs`, with 0 resolved to the available parallelism.
+    pub fn jobs(&self) -> usize {
+        match self.jobs {
+            0 => std::thread::available_parallelism().map_or(1, usize::from),
+            jobs => jobs,
         }
     }
 
+    pub fn discover_workspace(&self) -> Result<Workspace, EngineError> {
+        Workspace::discover_cancellable(
+            &self.path,
+            &self.workspace,
+            self.jobs(),
+            &self.cancellation,
+        )
+    }
+
+    pub fn with_disabled_checks<I, S>(mut self, ids: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.disabled_checks.extend(ids.into_iter().map(Into::into));
+        self
+    }
+
     pub fn path(&self) -> &Path {
         &self.path
     }
+
+    pub fn is_check_enabled(&self, check: &CheckMetadata) -> bool {
+        !self
+            .disabled_checks
+            .iter()
+            .any(|rule_id| check.implements(rule_id))
+    }
+}
+
+impl std::fmt::Debug for ScanRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ScanRequest")
+            .field("target", &self.target)
+            .field("path", &self.path)
+            .field("disabled_checks", &self.disabled_checks)
+            .field("workspace", &self.workspace)
+            .field("jobs", &self.jobs)
+            .field("check_budget", &self.check_budget)
+            .field("cancellation", &self.cancellation)
+            .field("cache", &self.cache.as_ref().map(|cache| cache.key()))
+            .finish_non_exhaustive()
+    }
+}
+
+/// Compares what is scanned and how; the observer, cancellation token and
+/// cache are handles to the scan in progress and are left out.
+impl PartialEq for ScanRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.target == other.target
+            && self.path == other.path
+            && self.disabled_checks == other.disabled_checks
+            && self.workspace == other.workspace
+            && self.jobs == other.jobs
+            && self.check_budget == other.check_budget
+    }
 }
 
+impl Eq for ScanRequest {}
+
 #[derive(Debug)]
 pub enum EngineError {
     InvalidRequest(String),
@@ -187,6 +760,8 @@ pub enum EngineError {
         path: Option<PathBuf>,
         source: std::io::Error,
     },
+    /// The request's `CancellationToken` was cancelled or expired.
+    Cancelled,
 }
 
 impl EngineError {
@@ -206,6 +781,7 @@ impl Display for EngineError {
                 Some(path) => write!(f, "I/O error at {}: {source}", path.display()),
                 None => write!(f, "I/O error: {source}"),
             },
+            Self::Cancelled => write!(f, "scan cancelled"),
         }
     }
 }
@@ -213,7 +789,7 @@ impl Display for EngineError {
 impl Error for EngineError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
-            Self::InvalidRequest(_) => None,
+            Self::InvalidRequest(_) | Self::Cancelled => None,
             Self::Io { source, .. } => Some(source),
         }
     }
@@ -222,5 +798,272 @@ impl Error for EngineError {
 pub trait ScanEngine {
     fn name(&self) -> &'static str;
     fn target(&self) -> TargetKind;
+
+    /// Reported in the scan metadata; engines return their own crate version.
+    fn version(&self) -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
     fn scan(&self, request: &ScanRequest) -> Result<ScanReport, EngineError>;
+
+    /// Scans an already walked workspace so several engines share one walk and
+    /// its content cache. Defaults to `scan`, which walks the path again.
+    fn scan_workspace(
+        &self,
+        request: &ScanRequest,
+        _workspace: &Workspace,
+    ) -> Result<ScanReport, EngineError> {
+        self.scan(request)
+    }
+
+    /// Metadata of the checks this engine runs, used to line findings up with the rule catalog.
+    fn checks(&self) -> Vec<CheckMetadata> {
+        Vec::new()
+    }
+
+    /// Whether the workspace holds files this engine scans; drives `--target auto`.
+    fn applies_to(&self, _workspace: &Workspace) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scan_requests_compare_what_is_scanned_not_their_handles() {
+        let request = ScanRequest::new(TargetKind::Web, "site").with_jobs(2);
+        assert_eq!(
+            request,
+            request
+                .clone()
+                .with_observer(Arc::new(NoopObserver))
+                .with_cancellation(CancellationToken::new())
+        );
+        assert_ne!(request, request.clone().with_jobs(4));
+        assert_ne!(
+            request,
+            request.clone().with_disabled_checks(["web.csp.required"])
+        );
+    }
+
+    #[test]
+    fn span_and_evidence_follow_matched_lines() {
+        let source =
+            "<manifest>\n  <application\n    android:debuggable=\"true\" />\n</manifest>\n";
+        let start = source.find("android:debuggable").unwrap();
+        let finding = Finding::new(
+            "mobile.android.debuggable-enabled",
+            "debuggable",
+            Severity::Medium,
+            TargetKind::Android,
+            "message",
+            "remediation",
+        )
+        .with_file("AndroidManifest.xml")
+        .with_match(source, start..start + "android:debuggable=\"true\"".len());
+
+        assert_eq!(
+            finding.span,
+            Some(SourceSpan {
+                line: 3,
+                column: 5,
+                end_line: 3,
+                end_column: 30,
+            })
+        );
+        assert_eq!(
+            finding.evidence.as_deref(),
+            Some("android:debuggable=\"true\" />")
+        );
+        assert_eq!(
+            finding.location_label().as_deref(),
+            Some("AndroidManifest.xml:3:5")
+        );
+    }
+
+    fn debuggable_finding(file: &str, source: &str) -> Finding {
+        let needle = "android:debuggable=\"true\"";
+        let start = source.find(needle).unwrap();
+        Finding::new(
+            "mobile.android.debuggable-enabled",
+            "debuggable",
+            Severity::Medium,
+            TargetKind::Android,
+            "message",
+            "remediation",
+        )
+        .with_file(file)
+        .with_match(source, start..start + needle.len())
+    }
+
+    #[test]
+    fn fingerprint_survives_line_shifts_but_not_path_changes() {
+        let original = debuggable_finding(
+            "app/AndroidManifest.xml",
+            "<application android:debuggable=\"true\" />",
+        );
+        let shifted = debuggable_finding(
+            "./app/AndroidManifest.xml",
+            "<!-- header -->\n\n  <application   android:debuggable=\"true\" />",
+        );
+        let other_file = debuggable_finding(
+            "lib/AndroidManifest.xml",
+            "<application android:debuggable=\"true\" />",
+        );
+
+        assert_ne!(original.span, shifted.span);
+        assert_eq!(
+            original.compute_fingerprint(),
+            shifted.compute_fingerprint()
+        );
+        assert_ne!(
+            original.compute_fingerprint(),
+            other_file.compute_fingerprint()
+        );
+        assert_eq!(original.compute_fingerprint().len(), FINGERPRINT_BYTES * 2);
+    }
+
+    #[test]
+    fn report_assigns_fingerprints_and_dedupes_repeated_hits() {
+        let source = "<application android:debuggable=\"true\" />";
+        let mut report = ScanReport::new(TargetKind::Mobile);
+        report.push(debuggable_finding("AndroidManifest.xml", source));
+        report.push(debuggable_finding("AndroidManifest.xml", source));
+        report.push(debuggable_finding("other/AndroidManifest.xml", source));
+
+        assert_eq!(report.fingerprints().count(), 3);
+        report.dedupe_findings();
+        assert_eq!(report.findings.len(), 2);
+        assert_eq!(report.status, ScanStatus::Warn);
+    }
+
+    #[test]
+    fn policy_mode_gates_the_evaluated_status() {
+        let source = "<application android:debuggable=\"true\" />";
+        let gated = |mode| {
+            let mut report = ScanReport::new(TargetKind::Mobile).with_policy(PolicyResult {
+                blocking_severity: Severity::Medium,
+                mode,
+                ..PolicyResult::default()
+            });
+            report.push(debuggable_finding("AndroidManifest.xml", source));
+            (report.status, report.policy.status)
+        };
+
+        assert_eq!(
+            gated(PolicyMode::Enforce),
+            (ScanStatus::Block, ScanStatus::Block)
+        );
+        assert_eq!(
+            gated(PolicyMode::Warn),
+            (ScanStatus::Warn, ScanStatus::Block)
+        );
+        assert_eq!(
+            gated(PolicyMode::Audit),
+            (ScanStatus::Pass, ScanStatus::Block)
+        );
+    }
+
+    #[test]
+    fn score_deducts_weights_and_blocks_below_min_score() {
+        let source = "<application android:debuggable=\"true\" />";
+        let mut report = ScanReport::new(TargetKind::Mobile);
+        assert_eq!(report.score, MAX_SCORE);
+
+        report.push(debuggable_finding("AndroidManifest.xml", source));
+        report.push(debuggable_finding("lib/AndroidManifest.xml", source));
+        assert_eq!(report.score, 90);
+        assert_eq!(report.status, ScanStatus::Warn);
+
+        let mut report = report.with_policy(PolicyResult {
+            min_score: Some(95),
+            score_weights: ScoreWeights {
+                medium: 60,
+                ..ScoreWeights::default()
+            },
+            ..PolicyResult::default()
+        });
+        assert_eq!(report.score, 0, "score is floored at zero");
+        assert!(report.below_min_score());
+        assert_eq!(report.status, ScanStatus::Block);
+
+        report.findings[0].exception = Some("EXC-1".to_string());
+        report.findings[1].exception = Some("EXC-2".to_string());
+        report.recompute_status();
+        assert_eq!(report.score, MAX_SCORE);
+        assert_eq!(report.status, ScanStatus::Pass);
+    }
+
+    #[cfg(feature = "serde")]
+    fn sample_report() -> ScanReport {
+        let mut report = ScanReport::new(TargetKind::Mobile);
+        report.push(
+            Finding::new(
+                "mobile.android.debuggable-enabled",
+                "Aplicação Android debuggable",
+                Severity::Medium,
+                TargetKind::Android,
+                "android:debuggable=\"true\" detectado no AndroidManifest.xml.",
+                "Garanta builds release com android:debuggable=false.",
+            )
+            .with_file("android/app/src/main/AndroidManifest.xml"),
+        );
+        report
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn enums_use_stable_lowercase_names() {
+        assert_eq!(
+            serde_json::to_string(&Severity::Critical).unwrap(),
+            "\"critical\""
+        );
+        assert_eq!(
+            serde_json::to_string(&ScanStatus::Block).unwrap(),
+            "\"block\""
+        );
+        assert_eq!(
+            serde_json::to_string(&TargetKind::Android).unwrap(),
+            "\"android\""
+        );
+        assert_eq!(
+            serde_json::from_str::<Severity>("\"medium\"").unwrap(),
+            Severity::Medium
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn scan_report_round_trips_through_json() {
+        let report = sample_report();
+        let raw = serde_json::to_string(&report).expect("serialize report");
+        let restored: ScanReport = serde_json::from_str(&raw).expect("deserialize report");
+
+        assert_eq!(restored, report);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn loads_findings_from_saved_cli_fixtures() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../samples/shared-fixtures/expected-findings");
+
+        for name in ["android-static-prod.json", "ios-static-prod.json"] {
+            let raw = std::fs::read_to_string(fixtures.join(name)).expect("read fixture");
+            let value: serde_json::Value = serde_json::from_str(&raw).expect("fixture json");
+            let summary = &value["summary"];
+
+            let status: ScanStatus =
+                serde_json::from_value(summary["status"].clone()).expect("status");
+            let findings: Vec<Finding> =
+                serde_json::from_value(summary["findings"].clone()).expect("findings");
+
+            assert_eq!(status, ScanStatus::Block);
+            assert!(!findings.is_empty());
+            let reserialized = serde_json::to_value(&findings).expect("serialize findings");
+            assert_eq!(reserialized, summary["findings"]);
+        }
+    }
 }
@@ -0,0 +1,197 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::CheckMetadata;
+
+/// What a scan covered and how long it took, for coverage audits and slow-scan
+/// investigations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScanMetadata {
+    /// RFC 3339 UTC timestamps.
+    pub started_at: String,
+    pub finished_at: String,
+    /// Wall-clock time in microseconds.
+    pub duration_us: u64,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub engines: Vec<EngineMetadata>,
+    /// Files walked and handed to the checks.
+    pub files_scanned: usize,
+    /// Entries skipped by the walk (binary, oversized, unreadable, ...).
+    pub files_skipped: usize,
+    pub checks: Vec<CheckRun>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub env: Option<String>,
+    /// Policy file the env was resolved from.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub policy_file: Option<PathBuf>,
+    /// `version` of the rule catalog (`rules.yaml`) in use.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub catalog_version: Option<u32>,
+}
+
+impl ScanMetadata {
+    pub fn new(started: SystemTime, finished: SystemTime) -> Self {
+        Self {
+            started_at: rfc3339(started),
+            finished_at: rfc3339(finished),
+            duration_us: micros(finished.duration_since(started).unwrap_or_default()),
+            engines: Vec::new(),
+            files_scanned: 0,
+            files_skipped: 0,
+            checks: Vec::new(),
+            env: None,
+            policy_file: None,
+            catalog_version: None,
+        }
+    }
+
+    /// Folds the metadata of another target's scan into this one: the time
+    /// span covers both, durations add up and engines and checks are appended.
+    pub fn merge(&mut self, other: ScanMetadata) {
+        // RFC 3339 UTC strings of equal precision sort chronologically.
+        if other.started_at < self.started_at {
+            self.started_at = other.started_at;
+        }
+        if other.finished_at > self.finished_at {
+            self.finished_at = other.finished_at;
+        }
+        self.duration_us += other.duration_us;
+        self.engines.extend(other.engines);
+        self.files_scanned = self.files_scanned.max(other.files_scanned);
+        self.files_skipped = self.files_skipped.max(other.files_skipped);
+        self.checks.extend(other.checks);
+        self.env = self.env.take().or(other.env);
+        self.policy_file = self.policy_file.take().or(other.policy_file);
+        self.catalog_version = self.catalog_version.or(other.catalog_version);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EngineMetadata {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum CheckRunStatus {
+    Completed,
+    /// Ended with a diagnostic (error or panic).
+    Failed,
+    /// Turned off by the policy; did not run.
+    Disabled,
+    /// Stopped, or never started, because the scan was cancelled.
+    Cancelled,
+    /// Stopped, or never started, because its budget or the scan timeout ran out.
+    TimedOut,
+}
+
+impl CheckRunStatus {
+    /// The check was stopped or never started by a cancellation or timeout.
+    pub fn is_unfinished(self) -> bool {
+        matches!(self, Self::Cancelled | Self::TimedOut)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Disabled => "disabled",
+            Self::Cancelled => "cancelled",
+            Self::TimedOut => "timed-out",
+        }
+    }
+}
+
+/// One check of a scan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CheckRun {
+    pub id: String,
+    pub rule_id: String,
+    pub status: CheckRunStatus,
+    pub duration_us: u64,
+    /// Files the check read, or whose results came from the cache.
+    pub files_considered: usize,
+    /// Unchanged files whose results were reused from the result cache.
+    pub files_cached: usize,
+    /// Distinct files the check reported findings on.
+    pub files_matched: usize,
+}
+
+impl CheckRun {
+    pub fn disabled(check: &CheckMetadata) -> Self {
+        Self::not_run(check, CheckRunStatus::Disabled)
+    }
+
+    /// Entry for a check that did not run at all.
+    pub fn not_run(check: &CheckMetadata, status: CheckRunStatus) -> Self {
+        Self {
+            id: check.id.to_string(),
+            rule_id: check.rule_id.to_string(),
+            status,
+            duration_us: 0,
+            files_considered: 0,
+            files_cached: 0,
+            files_matched: 0,
+        }
+    }
+}
+
+pub(crate) fn micros(duration: Duration) -> u64 {
+    u64::try_from(duration.as_micros()).unwrap_or(u64::MAX)
+}
+
+/// `YYYY-MM-DDTHH:MM:SS.mmmZ`, using the civil-from-days conversion of
+/// Howard Hinnant's algorithm.
+pub fn rfc3339(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let days = (seconds / 86_400) as i64;
+    let second_of_day = seconds % 86_400;
+
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        second_of_day / 3_600,
+        second_of_day % 3_600 / 60,
+        second_of_day % 60,
+        since_epoch.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_rfc3339_timestamps() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_792_235_045_123);
+        assert_eq!(rfc3339(time), "2026-10-17T11:04:05.123Z");
+        assert_eq!(rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+    }
+}
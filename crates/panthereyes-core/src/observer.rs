@@ -0,0 +1,32 @@
+use crate::{CheckMetadata, CheckRun, Finding, ScanReport, TargetKind, WorkspaceFile};
+
+/// Receives the events of a scan as it runs, for progress bars and streaming
+/// output. Set it with `ScanRequest::with_observer`; every method defaults to
+/// doing nothing.
+///
+/// Checks run on several threads, so check and file events of different checks
+/// interleave. Events of a single check keep their order.
+pub trait ScanObserver: Send + Sync {
+    /// An engine starts running `checks` enabled checks over `files` files.
+    fn scan_started(&self, _target: TargetKind, _files: usize, _checks: usize) {}
+
+    fn check_started(&self, _check: &CheckMetadata) {}
+
+    /// `check` read `file` through its `CheckContext`.
+    fn file_visited(&self, _check: &CheckMetadata, _file: &WorkspaceFile) {}
+
+    /// A check reported `finding`, before policy, exceptions and baseline are
+    /// applied. Repeated hits with the same fingerprint are emitted once.
+    fn finding_emitted(&self, _finding: &Finding) {}
+
+    fn check_finished(&self, _run: &CheckRun) {}
+
+    /// The engine is done; `report` holds its findings and metadata.
+    fn scan_finished(&self, _report: &ScanReport) {}
+}
+
+/// Observer of requests that did not set one.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopObserver;
+
+impl ScanObserver for NoopObserver {}
@@ -0,0 +1,345 @@
+use crate::{
+    BaselineSummary, CheckDiagnostic, CheckMetadata, EngineError, EngineMetadata, Finding,
+    PolicyResult, ScanEngine, ScanMetadata, ScanReport, ScanRequest, ScanStatus, ScanWarning,
+    TargetKind, Workspace, MAX_SCORE,
+};
+
+/// Runs several engines over one path, e.g. the web frontend and the mobile
+/// apps of a monorepo, and merges their reports.
+#[derive(Default)]
+pub struct ScanOrchestrator {
+    engines: Vec<Box<dyn ScanEngine>>,
+}
+
+impl ScanOrchestrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_engine(mut self, engine: impl ScanEngine + 'static) -> Self {
+        self.engines.push(Box::new(engine));
+        self
+    }
+
+    /// Targets of the registered engines, in registration order.
+    pub fn targets(&self) -> Vec<TargetKind> {
+        self.engines.iter().map(|engine| engine.target()).collect()
+    }
+
+    pub fn engine_for(&self, target: TargetKind) -> Option<&dyn ScanEngine> {
+        let target = target.engine_target();
+        self.engines
+            .iter()
+            .find(|engine| engine.target() == target)
+            .map(Box::as_ref)
+    }
+
+    /// Metadata of the checks of every engine.
+    pub fn checks(&self) -> Vec<CheckMetadata> {
+        self.engines
+            .iter()
+            .flat_map(|engine| engine.checks())
+            .collect()
+    }
+
+    /// Targets whose engine recognizes files of the workspace.
+    pub fn detect_targets(&self, workspace: &Workspace) -> Vec<TargetKind> {
+        self.engines
+            .iter()
+            .filter(|engine| engine.applies_to(workspace))
+            .map(|engine| engine.target())
+            .collect()
+    }
+
+    /// Runs the engine registered for `request.target`.
+    pub fn scan(&self, request: &ScanRequest) -> Result<ScanReport, EngineError> {
+        let engine = self.require_engine(request.target)?;
+        Ok(stamp_engine(engine, engine.scan(request)?))
+    }
+
+    /// Runs the engine registered for `request.target` over a workspace walked
+    /// beforehand, typically shared by the scans of several targets.
+    pub fn scan_workspace(
+        &self,
+        request: &ScanRequest,
+        workspace: &Workspace,
+    ) -> Result<ScanReport, EngineError> {
+        let engine = self.require_engine(request.target)?;
+        Ok(stamp_engine(
+            engine,
+            engine.scan_workspace(request, workspace)?,
+        ))
+    }
+
+    /// Runs `request` once per target over a single walk and merges the reports.
+    pub fn scan_targets(
+        &self,
+        request: &ScanRequest,
+        targets: &[TargetKind],
+    ) -> Result<CombinedReport, EngineError> {
+        let workspace = request.discover_workspace()?;
+        let reports = targets
+            .iter()
+            .map(|target| {
+                let mut request = request.clone();
+                request.target = *target;
+                self.scan_workspace(&request, &workspace)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(CombinedReport::from_reports(reports))
+    }
+
+    fn require_engine(&self, target: TargetKind) -> Result<&dyn ScanEngine, EngineError> {
+        self.engine_for(target).ok_or_else(|| {
+            EngineError::InvalidRequest(format!(
+                "no engine registered for target '{}'",
+                target.as_str()
+            ))
+        })
+    }
+}
+
+/// Records the engine that produced `report` in its metadata.
+fn stamp_engine(engine: &dyn ScanEngine, mut report: ScanReport) -> ScanReport {
+    if let Some(metadata) = &mut report.metadata {
+        metadata.engines.push(EngineMetadata {
+            name: engine.name().to_string(),
+            version: engine.version().to_string(),
+        });
+    }
+    report
+}
+
+impl std::fmt::Debug for ScanOrchestrator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.engines.iter().map(|engine| engine.name()))
+            .finish()
+    }
+}
+
+/// Outcome of one target inside a `CombinedReport`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TargetSummary {
+    pub target: TargetKind,
+    pub status: ScanStatus,
+    pub score: u8,
+    pub policy: PolicyResult,
+    pub findings: usize,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub baseline: Option<BaselineSummary>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    pub incomplete: bool,
+}
+
+/// Reports of several targets merged into one. The status is the worst target
+/// status and the score the lowest target score.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CombinedReport {
+    pub status: ScanStatus,
+    pub score: u8,
+    pub targets: Vec<TargetSummary>,
+    pub findings: Vec<Finding>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub suppressed: Vec<Finding>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub baseline: Option<BaselineSummary>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub warnings: Vec<ScanWarning>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub diagnostics: Vec<CheckDiagnostic>,
+    /// Some target scan did not finish; see `ScanReport::incomplete`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    pub incomplete: bool,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub unfinished_checks: Vec<String>,
+    /// Metadata of the target scans merged; see `ScanMetadata::merge`.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub metadata: Option<ScanMetadata>,
+}
+
+impl CombinedReport {
+    /// Merges already gated reports; each keeps the policy it was evaluated with.
+    pub fn from_reports(reports: impl IntoIterator<Item = ScanReport>) -> Self {
+        let mut combined = Self {
+            status: ScanStatus::Pass,
+            score: MAX_SCORE,
+            targets: Vec::new(),
+            findings: Vec::new(),
+            suppressed: Vec::new(),
+            baseline: None,
+            warnings: Vec::new(),
+            diagnostics: Vec::new(),
+            incomplete: false,
+            unfinished_checks: Vec::new(),
+            metadata: None,
+        };
+
+        for report in reports {
+            combined.status = combined.status.max(report.status);
+            combined.score = combined.score.min(report.score);
+            if let Some(baseline) = &report.baseline {
+                let merged = combined.baseline.get_or_insert_with(Default::default);
+                merged.new += baseline.new;
+                merged.existing += baseline.existing;
+                merged.fixed.extend(baseline.fixed.iter().cloned());
+            }
+            combined.targets.push(TargetSummary {
+                target: report.target,
+                status: report.status,
+                score: report.score,
+                policy: report.policy,
+                findings: report.findings.len(),
+                baseline: report.baseline,
+                incomplete: report.incomplete,
+            });
+            combined.incomplete |= report.incomplete;
+            combined.unfinished_checks.extend(report.unfinished_checks);
+            combined.findings.extend(report.findings);
+            combined.suppressed.extend(report.suppressed);
+            combined.warnings.extend(report.warnings);
+            combined.diagnostics.extend(report.diagnostics);
+            if let Some(metadata) = report.metadata {
+                match &mut combined.metadata {
+                    Some(merged) => merged.merge(metadata),
+                    None => combined.metadata = Some(metadata),
+                }
+            }
+        }
+
+        combined
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use crate::Severity;
+
+    struct FixedEngine {
+        target: TargetKind,
+        marker: &'static str,
+        severity: Option<Severity>,
+    }
+
+    impl ScanEngine for FixedEngine {
+        fn name(&self) -> &'static str {
+            "fixed"
+        }
+
+        fn target(&self) -> TargetKind {
+            self.target
+        }
+
+        fn scan(&self, request: &ScanRequest) -> Result<ScanReport, EngineError> {
+            let mut report = ScanReport::new(request.target);
+            if let Some(severity) = self.severity {
+                report.push(Finding::new(
+                    format!("{}.finding", self.target.as_str()),
+                    "t",
+                    severity,
+                    self.target,
+                    "m",
+                    "r",
+                ));
+            }
+            Ok(report)
+        }
+
+        fn applies_to(&self, workspace: &Workspace) -> bool {
+            workspace.find_by_name(self.marker).next().is_some()
+        }
+    }
+
+    fn orchestrator() -> ScanOrchestrator {
+        ScanOrchestrator::new()
+            .with_engine(FixedEngine {
+                target: TargetKind::Web,
+                marker: "nginx.conf",
+                severity: Some(Severity::Medium),
+            })
+            .with_engine(FixedEngine {
+                target: TargetKind::Mobile,
+                marker: "AndroidManifest.xml",
+                severity: Some(Severity::High),
+            })
+    }
+
+    #[test]
+    fn detects_targets_from_workspace_files() {
+        let root = TempDir::new("panthereyes-core-orchestrator");
+        root.write("android/AndroidManifest.xml", "<manifest/>");
+
+        let request = ScanRequest::new(TargetKind::Web, root.path());
+        let orchestrator = orchestrator();
+        let workspace = request.discover_workspace().expect("walk");
+        assert_eq!(
+            orchestrator.detect_targets(&workspace),
+            [TargetKind::Mobile]
+        );
+
+        root.write("nginx.conf", "server {}");
+        let workspace = request.discover_workspace().expect("walk");
+        assert_eq!(
+            orchestrator.detect_targets(&workspace),
+            [TargetKind::Web, TargetKind::Mobile]
+        );
+    }
+
+    #[test]
+    fn merges_reports_with_per_target_status() {
+        let request = ScanRequest::new(TargetKind::Web, ".");
+        let combined = orchestrator()
+            .scan_targets(&request, &[TargetKind::Web, TargetKind::Mobile])
+            .expect("scan");
+
+        assert_eq!(combined.status, ScanStatus::Block);
+        assert_eq!(combined.score, 85);
+        assert_eq!(combined.findings.len(), 2);
+        let statuses: Vec<_> = combined
+            .targets
+            .iter()
+            .map(|target| (target.target, target.status, target.findings))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                (TargetKind::Web, ScanStatus::Warn, 1),
+                (TargetKind::Mobile, ScanStatus::Block, 1),
+            ]
+        );
+
+        let empty = CombinedReport::from_reports([]);
+        assert_eq!((empty.status, empty.score), (ScanStatus::Pass, MAX_SCORE));
+    }
+}
@@ -0,0 +1,47 @@
+use crate::{Finding, Severity};
+
+pub const MAX_SCORE: u8 = 100;
+
+/// Points deducted from `MAX_SCORE` for each gating finding, by severity.
+///
+/// Defaults: low 2, medium 5, high 15, critical 30. Policies can override
+/// them with the `scoreWeightLow|Medium|High|Critical` directives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScoreWeights {
+    pub low: u32,
+    pub medium: u32,
+    pub high: u32,
+    pub critical: u32,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            low: 2,
+            medium: 5,
+            high: 15,
+            critical: 30,
+        }
+    }
+}
+
+impl ScoreWeights {
+    pub fn weight(&self, severity: Severity) -> u32 {
+        match severity {
+            Severity::Low => self.low,
+            Severity::Medium => self.medium,
+            Severity::High => self.high,
+            Severity::Critical => self.critical,
+        }
+    }
+
+    /// `MAX_SCORE` minus the weights of `findings`, floored at 0.
+    pub fn score<'a>(&self, findings: impl IntoIterator<Item = &'a Finding>) -> u8 {
+        let penalty = findings
+            .into_iter()
+            .map(|finding| self.weight(finding.severity))
+            .fold(0u32, u32::saturating_add);
+        MAX_SCORE.saturating_sub(penalty.min(u32::from(MAX_SCORE)) as u8)
+    }
+}
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::{CheckMetadata, ScanReport, ScanWarning, Workspace};
+
+/// Comment marker for inline suppressions: `panthereyes-ignore <rules>: <reason>`,
+/// inside whatever comment syntax the file uses (`<!-- -->`, `#`, `//`).
+pub const SUPPRESSION_MARKER: &str = "panthereyes-ignore";
+
+/// Inline suppression comment that silenced a finding, recorded on the
+/// findings listed under `ScanReport::suppressed`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InlineSuppression {
+    /// 1-based line of the comment.
+    pub line: usize,
+    /// Finding ids or catalog rule ids named by the comment.
+    pub rules: Vec<String>,
+    pub reason: String,
+}
+
+impl InlineSuppression {
+    pub fn covers(&self, check: &CheckMetadata) -> bool {
+        self.rules.iter().any(|rule| check.implements(rule))
+    }
+}
+
+/// A parsed comment and the lines it covers: its own line (trailing comment)
+/// through the next line that is not blank nor another suppression, extended
+/// to the end of the tag when that line opens a multi-line XML element.
+#[derive(Debug)]
+struct Comment {
+    suppression: InlineSuppression,
+    lines: RangeInclusive<usize>,
+    used: bool,
+    /// Some completed check named by the comment read the file.
+    expected: bool,
+}
+
+impl Comment {
+    fn is_valid(&self) -> bool {
+        !self.suppression.rules.is_empty() && !self.suppression.reason.is_empty()
+    }
+}
+
+/// Suppression comments of the files seen by the checks of one engine scan,
+/// parsed on first use.
+pub(crate) struct Suppressions<'a> {
+    workspace: &'a Workspace,
+    files: Mutex<HashMap<PathBuf, Vec<Comment>>>,
+}
+
+impl<'a> Suppressions<'a> {
+    pub(crate) fn new(workspace: &'a Workspace) -> Self {
+        Self {
+            workspace,
+            files: Mutex::default(),
+        }
+    }
+
+    /// Moves the findings of `check` covered by a comment naming it to
+    /// `partial.suppressed`. `read` are the files the check considered;
+    /// `completed` marks a run whose silence counts against unused comments.
+    pub(crate) fn apply(
+        &self,
+        check: &CheckMetadata,
+        read: &[PathBuf],
+        completed: bool,
+        partial: &mut ScanReport,
+    ) {
+        let mut files = self.lock();
+        for path in read {
+            let comments = self.comments(&mut files, path);
+            if completed {
+                for comment in comments.iter_mut() {
+                    comment.expected |= comment.suppression.covers(check);
+                }
+            }
+        }
+
+        let mut kept = Vec::new();
+        for mut finding in std::mem::take(&mut partial.findings) {
+            let comment = match (&finding.file, finding.span) {
+                (Some(file), Some(span)) => {
+                    self.comments(&mut files, file).iter_mut().find(|comment| {
+                        comment.is_valid()
+                            && comment.lines.contains(&span.line)
+                            && comment.suppression.covers(check)
+                    })
+                }
+                _ => None,
+            };
+            match comment {
+                Some(comment) => {
+                    comment.used = true;
+                    finding.suppression = Some(comment.suppression.clone());
+                    partial.suppressed.push(finding);
+                }
+                None => kept.push(finding),
+            }
+        }
+        partial.findings = kept;
+        partial.recompute_status();
+    }
+
+    /// Warnings for comments without rules or reason, and for comments that
+    /// silenced nothing although a check they name read the file.
+    pub(crate) fn warnings(self) -> Vec<ScanWarning> {
+        let files = self
+            .files
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut files: Vec<_> = files.into_iter().collect();
+        files.sort_by(|left, right| left.0.cmp(&right.0));
+
+        let mut warnings = Vec::new();
+        for (path, comments) in files {
+            for comment in comments {
+                let location = format!("{}:{}", path.display(), comment.suppression.line);
+                let warning = if !comment.is_valid() {
+                    ScanWarning::new(
+                        "suppression.invalid",
+                        format!(
+                            "Supressao em {location} ignorada: use '{SUPPRESSION_MARKER} <regra>: <motivo>' com a regra e o motivo."
+                        ),
+                    )
+                } else if comment.expected && !comment.used {
+                    ScanWarning::new(
+                        "suppression.unused",
+                        format!(
+                            "Supressao em {location} ({}) nao corresponde a nenhum achado; remova-a.",
+                            comment.suppression.rules.join(", ")
+                        ),
+                    )
+                } else {
+                    continue;
+                };
+                warnings.push(warning.with_subject(location));
+            }
+        }
+        warnings
+    }
+
+    fn comments<'f>(
+        &self,
+        files: &'f mut HashMap<PathBuf, Vec<Comment>>,
+        path: &Path,
+    ) -> &'f mut Vec<Comment> {
+        files.entry(path.to_path_buf()).or_insert_with(|| {
+            self.workspace
+                .file(path)
+                .and_then(|file| self.workspace.read_to_string(file).ok())
+                .map(|source| parse_comments(&source))
+                .unwrap_or_default()
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, Vec<Comment>>> {
+        self.files
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn parse_comments(source: &str) -> Vec<Comment> {
+    if !source.contains(SUPPRESSION_MARKER) {
+        return Vec::new();
+    }
+    let lines: Vec<&str> = source.lines().collect();
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let suppression = parse_suppression(line, index + 1)?;
+            Some(Comment {
+                suppression,
+                lines: index + 1..=covered_until(&lines, index),
+                used: false,
+                expected: false,
+            })
+        })
+        .collect()
+}
+
+fn parse_suppression(line: &str, number: usize) -> Option<InlineSuppression> {
+    let start = line.find(SUPPRESSION_MARKER)? + SUPPRESSION_MARKER.len();
+    let rest = &line[start..];
+    // `panthereyes-ignore-file` and the like are other directives.
+    if rest.chars().next().is_some_and(|c| !c.is_whitespace()) {
+        return None;
+    }
+    let rest = rest.trim();
+    let rest = rest
+        .strip_suffix("-->")
+        .or_else(|| rest.strip_suffix("*/"))
+        .unwrap_or(rest);
+    let (rules, reason) = rest.split_once(':').unwrap_or((rest, ""));
+    Some(InlineSuppression {
+        line: number,
+        rules: rules
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|rule| !rule.is_empty())
+            .map(str::to_string)
+            .collect(),
+        reason: reason.trim().to_string(),
+    })
+}
+
+/// Last 1-based line covered by the comment at `index`.
+fn covered_until(lines: &[&str], index: usize) -> usize {
+    let Some(target) = (index + 1..lines.len())
+        .find(|&next| !lines[next].trim().is_empty() && !lines[next].contains(SUPPRESSION_MARKER))
+    else {
+        return index + 1;
+    };
+    let mut end = target;
+    let mut open = tag_balance(lines[target]);
+    while open > 0 && end + 1 < lines.len() {
+        end += 1;
+        open += tag_balance(lines[end]);
+    }
+    end + 1
+}
+
+fn tag_balance(line: &str) -> isize {
+    line.matches('<').count() as isize - line.matches('>').count() as isize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_comments_and_the_lines_they_cover() {
+        let source = "<manifest>\n  <!-- panthereyes-ignore mobile.android.debuggable-enabled, mobile.debug.disabled: build interno -->\n\n  <application\n    android:debuggable=\"true\">\n  </application>\n  <!-- panthereyes-ignore mobile.android.cleartext-traffic-enabled -->\n  <!-- panthereyes-ignore-file -->\n</manifest>\n";
+        let comments = parse_comments(source);
+        assert_eq!(comments.len(), 2);
+
+        assert_eq!(
+            comments[0].suppression,
+            InlineSuppression {
+                line: 2,
+                rules: vec![
+                    "mobile.android.debuggable-enabled".to_string(),
+                    "mobile.debug.disabled".to_string()
+                ],
+                reason: "build interno".to_string(),
+            }
+        );
+        assert_eq!(comments[0].lines, 2..=5);
+        assert!(comments[0].is_valid());
+
+        assert_eq!(comments[1].suppression.line, 7);
+        assert!(!comments[1].is_valid());
+
+        let nginx = parse_comments(
+            "server {\n  listen 443 ssl; # panthereyes-ignore web.hsts.required: TLS no LB\n}\n",
+        );
+        assert_eq!(nginx[0].lines, 2..=3);
+        assert_eq!(nginx[0].suppression.reason, "TLS no LB");
+    }
+}
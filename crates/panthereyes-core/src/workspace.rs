@@ -0,0 +1,649 @@
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::overrides::OverrideBuilder;
+use ignore::{DirEntry, WalkBuilder, WalkState};
+use sha2::{Digest, Sha256};
+
+use crate::{CancellationToken, EngineError, ScanWarning};
+
+/// Gitignore-style file read from any directory of the scanned tree.
+pub const PANTHEREYES_IGNORE_FILE: &str = ".panthereyesignore";
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024;
+/// Directories never worth scanning (VCS metadata, dependencies, build output).
+pub const DEFAULT_EXCLUDED_DIRS: &[&str] = &[
+    ".git",
+    "node_modules",
+    "Pods",
+    "build",
+    ".gradle",
+    "DerivedData",
+];
+
+/// Where the CLI keeps its result cache, relative to the scan path; never walked.
+pub const RESULT_CACHE_DIR: &str = ".panthereyes/cache";
+
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
+
+/// Which files of the scan path engines get to see.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceOptions {
+    /// When non-empty, only files matching one of these globs are kept.
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub max_file_size: u64,
+}
+
+impl Default for WorkspaceOptions {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceFile {
+    pub path: PathBuf,
+    /// Path relative to the workspace root, as reported on findings.
+    pub relative: PathBuf,
+    pub size: u64,
+}
+
+impl WorkspaceFile {
+    pub fn file_name(&self) -> Option<&str> {
+        self.path.file_name().and_then(|name| name.to_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SkipReason {
+    Binary,
+    TooLarge { size: u64, limit: u64 },
+    SymlinkOutsideRoot,
+    SymlinkLoop,
+    Unreadable(String),
+}
+
+impl SkipReason {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Binary => "workspace.binary",
+            Self::TooLarge { .. } => "workspace.too-large",
+            Self::SymlinkOutsideRoot => "workspace.symlink-outside-root",
+            Self::SymlinkLoop => "workspace.symlink-loop",
+            Self::Unreadable(_) => "workspace.unreadable",
+        }
+    }
+}
+
+impl Display for SkipReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Binary => write!(f, "arquivo binario"),
+            Self::TooLarge { size, limit } => {
+                write!(
+                    f,
+                    "arquivo com {size} bytes excede o limite de {limit} bytes"
+                )
+            }
+            Self::SymlinkOutsideRoot => write!(f, "symlink aponta para fora do caminho escaneado"),
+            Self::SymlinkLoop => write!(f, "loop de symlinks"),
+            Self::Unreadable(message) => write!(f, "entrada ilegivel: {message}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkippedEntry {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
+/// Files under a scan path, walked once and shared by the checks of every engine.
+///
+/// Honors `.gitignore` and `.panthereyesignore`, the include/exclude globs of
+/// `WorkspaceOptions` and `DEFAULT_EXCLUDED_DIRS`. Binary and oversized files,
+/// symlinks leaving the root or looping, and unreadable entries are skipped and
+/// recorded instead of failing the walk. File contents are read at most once
+/// and cached, so checks running concurrently share them.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    root: PathBuf,
+    files: Vec<WorkspaceFile>,
+    skipped: Vec<SkippedEntry>,
+    /// The walk was stopped by its cancellation token before the end.
+    truncated: bool,
+    /// Read-once contents, by index in `files`. Shared with the views made by
+    /// `without`.
+    contents: Vec<Arc<ContentSlot>>,
+}
+
+#[derive(Debug, Default)]
+struct ContentSlot {
+    contents: OnceLock<Result<Arc<str>, Arc<std::io::Error>>>,
+    /// Hex SHA-256 of `contents`.
+    hash: OnceLock<String>,
+}
+
+impl Workspace {
+    pub fn discover(
+        root: impl Into<PathBuf>,
+        options: &WorkspaceOptions,
+    ) -> Result<Self, EngineError> {
+        Self::discover_with_jobs(root, options, 1)
+    }
+
+    /// Same as `discover`, walking directories on `jobs` threads. Files and
+    /// skipped entries are sorted afterwards, so the result does not depend on
+    /// thread scheduling.
+    pub fn discover_with_jobs(
+        root: impl Into<PathBuf>,
+        options: &WorkspaceOptions,
+        jobs: usize,
+    ) -> Result<Self, EngineError> {
+        Self::discover_cancellable(root, options, jobs, &CancellationToken::new())
+    }
+
+    /// Same as `discover_with_jobs`, stopping the walk once `cancellation`
+    /// says so. The files found until then are kept and `is_complete` is false.
+    pub fn discover_cancellable(
+        root: impl Into<PathBuf>,
+        options: &WorkspaceOptions,
+        jobs: usize,
+        cancellation: &CancellationToken,
+    ) -> Result<Self, EngineError> {
+        let root = root.into();
+        if !root.exists() {
+            return Err(EngineError::InvalidRequest(format!(
+                "scan path not found: {}",
+                root.display()
+            )));
+        }
+        let canonical_root =
+            fs::canonicalize(&root).map_err(|source| EngineError::io(root.clone(), source))?;
+        let base = if root.is_file() {
+            root.parent().unwrap_or(Path::new("")).to_path_buf()
+        } else {
+            root.clone()
+        };
+
+        // Excludes prune the walk; includes only narrow what is left, so they
+        // cannot re-include files hidden by ignore files.
+        let include = build_include_set(&options.include)?;
+        let mut overrides = OverrideBuilder::new(&base);
+        for glob in &options.exclude {
+            overrides.add(&format!("!{glob}")).map_err(|err| {
+                EngineError::InvalidRequest(format!("invalid workspace glob '{glob}': {err}"))
+            })?;
+        }
+        let overrides = overrides.build().map_err(|err| {
+            EngineError::InvalidRequest(format!("invalid workspace globs: {err}"))
+        })?;
+
+        // Symlinked directories are followed only while they stay under the
+        // root; the ones leaving it are pruned here, before the walk descends.
+        let escaped = Arc::new(Mutex::new(Vec::new()));
+        let prune = {
+            let escaped = Arc::clone(&escaped);
+            let canonical_root = canonical_root.clone();
+            move |entry: &DirEntry| {
+                if entry.depth() == 0 || !entry.file_type().is_some_and(|kind| kind.is_dir()) {
+                    return true;
+                }
+                if is_excluded_dir(entry.path()) {
+                    return false;
+                }
+                if entry.path_is_symlink() && !resolves_under(&canonical_root, entry.path()) {
+                    escaped
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .push(SkippedEntry {
+                            path: entry.path().to_path_buf(),
+                            reason: SkipReason::SymlinkOutsideRoot,
+                        });
+                    return false;
+                }
+                true
+            }
+        };
+        let walker = WalkBuilder::new(&root)
+            .hidden(false)
+            .git_global(false)
+            .require_git(false)
+            .follow_links(true)
+            .add_custom_ignore_filename(PANTHEREYES_IGNORE_FILE)
+            .overrides(overrides)
+            .filter_entry(prune)
+            .threads(jobs.max(1))
+            .build_parallel();
+
+        let filter = WalkFilter {
+            root: &root,
+            canonical_root: &canonical_root,
+            include: include.as_ref(),
+            max_file_size: options.max_file_size,
+        };
+        let visits = Mutex::new(Vec::new());
+        let truncated = AtomicBool::new(false);
+        walker.run(|| {
+            let (filter, visits, truncated) = (&filter, &visits, &truncated);
+            Box::new(move |entry| {
+                if cancellation.should_stop() {
+                    truncated.store(true, Ordering::Relaxed);
+                    return WalkState::Quit;
+                }
+                if let Some(visit) = filter.visit(entry) {
+                    visits
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .push(visit);
+                }
+                WalkState::Continue
+            })
+        });
+
+        let mut files = Vec::new();
+        let mut skipped = std::mem::take(
+            &mut *escaped
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        );
+        for visit in visits
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+        {
+            match visit {
+                Visit::File(file) => files.push(file),
+                Visit::Skipped(entry) => skipped.push(entry),
+            }
+        }
+        files.sort_by(|left, right| left.relative.cmp(&right.relative));
+        skipped.sort_by(|left, right| left.path.cmp(&right.path));
+
+        Ok(Self {
+            contents: files.iter().map(|_| Arc::default()).collect(),
+            root,
+            files,
+            skipped,
+            truncated: truncated.into_inner(),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn files(&self) -> &[WorkspaceFile] {
+        &self.files
+    }
+
+    pub fn skipped(&self) -> &[SkippedEntry] {
+        &self.skipped
+    }
+
+    /// Whether the walk reached every file; false when it was cancelled.
+    pub fn is_complete(&self) -> bool {
+        !self.truncated
+    }
+
+    /// Files whose name matches `file_name`, ignoring ASCII case.
+    pub fn find_by_name<'a>(
+        &'a self,
+        file_name: &'a str,
+    ) -> impl Iterator<Item = &'a WorkspaceFile> + 'a {
+        self.files.iter().filter(move |file| {
+            file.file_name()
+                .is_some_and(|name| name.eq_ignore_ascii_case(file_name))
+        })
+    }
+
+    /// Contents of a workspace file, read from disk on first use only. Files
+    /// from another workspace are read without caching.
+    pub fn read_to_string(&self, file: &WorkspaceFile) -> Result<Arc<str>, EngineError> {
+        let Some(slot) = self.slot(file) else {
+            return read_file(&file.path)
+                .map_err(|source| EngineError::io(file.path.clone(), source));
+        };
+
+        slot.contents
+            .get_or_init(|| read_file(&file.path).map_err(Arc::new))
+            .clone()
+            .map_err(|source| {
+                let source = std::io::Error::new(source.kind(), source.to_string());
+                EngineError::io(file.path.clone(), source)
+            })
+    }
+
+    /// Hex SHA-256 of the file contents, computed once per file.
+    pub fn content_hash(&self, file: &WorkspaceFile) -> Result<String, EngineError> {
+        let contents = self.read_to_string(file)?;
+        let hash = || format!("{:x}", Sha256::digest(contents.as_bytes()));
+        Ok(match self.slot(file) {
+            Some(slot) => slot.hash.get_or_init(hash).clone(),
+            None => hash(),
+        })
+    }
+
+    /// View of this workspace without the files at the `skipped` relative
+    /// paths. Contents read through either are shared.
+    pub(crate) fn without(&self, skipped: &HashSet<&Path>) -> Self {
+        let (files, contents) = self
+            .files
+            .iter()
+            .zip(&self.contents)
+            .filter(|(file, _)| !skipped.contains(file.relative.as_path()))
+            .map(|(file, slot)| (file.clone(), Arc::clone(slot)))
+            .unzip();
+        Self {
+            root: self.root.clone(),
+            files,
+            skipped: self.skipped.clone(),
+            truncated: self.truncated,
+            contents,
+        }
+    }
+
+    /// Workspace file at a relative path.
+    pub(crate) fn file(&self, relative: &Path) -> Option<&WorkspaceFile> {
+        self.files
+            .binary_search_by(|candidate| candidate.relative.as_path().cmp(relative))
+            .ok()
+            .map(|index| &self.files[index])
+    }
+
+    fn slot(&self, file: &WorkspaceFile) -> Option<&ContentSlot> {
+        self.files
+            .binary_search_by(|candidate| candidate.relative.cmp(&file.relative))
+            .ok()
+            .filter(|&index| self.files[index].path == file.path)
+            .map(|index| self.contents[index].as_ref())
+    }
+
+    /// Warnings for skipped entries that may hide findings; binary files are
+    /// skipped silently.
+    pub fn warnings(&self) -> Vec<ScanWarning> {
+        self.skipped
+            .iter()
+            .filter(|entry| entry.reason != SkipReason::Binary)
+            .map(|entry| {
+                let relative = self.relative_path(&entry.path);
+                ScanWarning::new(
+                    entry.reason.code(),
+                    format!("{} ignorado: {}.", relative.display(), entry.reason),
+                )
+                .with_subject(relative.to_string_lossy())
+            })
+            .collect()
+    }
+
+    pub(crate) fn relative_path(&self, path: &Path) -> PathBuf {
+        relative_to(&self.root, path)
+    }
+}
+
+enum Visit {
+    File(WorkspaceFile),
+    Skipped(SkippedEntry),
+}
+
+/// Per-entry decisions of the walk, shared by the walker threads.
+struct WalkFilter<'a> {
+    root: &'a Path,
+    canonical_root: &'a Path,
+    include: Option<&'a GlobSet>,
+    max_file_size: u64,
+}
+
+impl WalkFilter<'_> {
+    fn visit(&self, entry: Result<DirEntry, ignore::Error>) -> Option<Visit> {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                let path = walk_error_path(&err).unwrap_or_else(|| self.root.to_path_buf());
+                let reason = if is_loop(&err) {
+                    SkipReason::SymlinkLoop
+                } else {
+                    SkipReason::Unreadable(err.to_string())
+                };
+                return Some(skip(path, reason));
+            }
+        };
+        if !entry.file_type().is_some_and(|kind| kind.is_file()) {
+            return None;
+        }
+
+        let path = entry.path().to_path_buf();
+        if entry.path_is_symlink() && !resolves_under(self.canonical_root, &path) {
+            return Some(skip(path, SkipReason::SymlinkOutsideRoot));
+        }
+
+        let size = match entry.metadata() {
+            Ok(metadata) => metadata.len(),
+            Err(err) => return Some(skip(path, SkipReason::Unreadable(err.to_string()))),
+        };
+        if size > self.max_file_size {
+            let reason = SkipReason::TooLarge {
+                size,
+                limit: self.max_file_size,
+            };
+            return Some(skip(path, reason));
+        }
+        match looks_binary(&path) {
+            Ok(false) => {}
+            Ok(true) => return Some(skip(path, SkipReason::Binary)),
+            Err(err) => return Some(skip(path, SkipReason::Unreadable(err.to_string()))),
+        }
+
+        let relative = relative_to(self.root, &path);
+        if self
+            .include
+            .is_some_and(|include| !include.is_match(&relative))
+        {
+            return None;
+        }
+        Some(Visit::File(WorkspaceFile {
+            path,
+            relative,
+            size,
+        }))
+    }
+}
+
+fn skip(path: PathBuf, reason: SkipReason) -> Visit {
+    Visit::Skipped(SkippedEntry { path, reason })
+}
+
+fn resolves_under(canonical_root: &Path, path: &Path) -> bool {
+    fs::canonicalize(path).is_ok_and(|target| target.starts_with(canonical_root))
+}
+
+/// Default excluded directories, plus the result-cache directory.
+fn is_excluded_dir(path: &Path) -> bool {
+    let name = path.file_name().and_then(|name| name.to_str());
+    name.is_some_and(|name| DEFAULT_EXCLUDED_DIRS.contains(&name))
+        || path.ends_with(RESULT_CACHE_DIR)
+}
+
+/// Path relative to the root; a file root is reported by its file name.
+fn relative_to(root: &Path, path: &Path) -> PathBuf {
+    if path == root {
+        return path
+            .file_name()
+            .map_or_else(|| path.to_path_buf(), PathBuf::from);
+    }
+    path.strip_prefix(root)
+        .map_or_else(|_| path.to_path_buf(), Path::to_path_buf)
+}
+
+fn read_file(path: &Path) -> std::io::Result<Arc<str>> {
+    fs::read_to_string(path).map(Arc::from)
+}
+
+fn build_include_set(globs: &[String]) -> Result<Option<GlobSet>, EngineError> {
+    if globs.is_empty() {
+        return Ok(None);
+    }
+    let mut set = GlobSetBuilder::new();
+    for glob in globs {
+        let compiled = GlobBuilder::new(glob)
+            .literal_separator(true)
+            .build()
+            .map_err(|err| {
+                EngineError::InvalidRequest(format!("invalid workspace glob '{glob}': {err}"))
+            })?;
+        set.add(compiled);
+    }
+    set.build()
+        .map(Some)
+        .map_err(|err| EngineError::InvalidRequest(format!("invalid workspace globs: {err}")))
+}
+
+fn looks_binary(path: &Path) -> std::io::Result<bool> {
+    let mut head = Vec::with_capacity(BINARY_SNIFF_BYTES);
+    File::open(path)?
+        .take(BINARY_SNIFF_BYTES as u64)
+        .read_to_end(&mut head)?;
+    Ok(head.contains(&0))
+}
+
+fn walk_error_path(err: &ignore::Error) -> Option<PathBuf> {
+    match err {
+        ignore::Error::WithPath { path, .. } => Some(path.clone()),
+        ignore::Error::Loop { child, .. } => Some(child.clone()),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            walk_error_path(err)
+        }
+        ignore::Error::Partial(errors) => errors.first().and_then(walk_error_path),
+        _ => None,
+    }
+}
+
+fn is_loop(err: &ignore::Error) -> bool {
+    match err {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => is_loop(err),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{write_file, TempDir};
+
+    fn relative_files(workspace: &Workspace) -> Vec<String> {
+        workspace
+            .files()
+            .iter()
+            .map(|file| file.relative.to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn honors_ignore_files_default_dirs_and_globs() {
+        let root = TempDir::new("panthereyes-workspace-ignore");
+        write_file(&root.join(".gitignore"), b"dist/\n");
+        write_file(&root.join(".panthereyesignore"), b"*.generated.conf\n");
+        write_file(&root.join("nginx.conf"), b"server {}");
+        write_file(&root.join("site.generated.conf"), b"server {}");
+        write_file(&root.join("dist/nginx.conf"), b"server {}");
+        write_file(&root.join("node_modules/pkg/nginx.conf"), b"server {}");
+        write_file(&root.join("ios/Pods/Info.plist"), b"<plist/>");
+        write_file(&root.join("ios/App/Info.plist"), b"<plist/>");
+        write_file(&root.join("vendor/nginx.conf"), b"server {}");
+        write_file(&root.join(".panthereyes/cache/results.json"), b"{}");
+
+        let workspace =
+            Workspace::discover(root.path(), &WorkspaceOptions::default()).expect("walk");
+        assert_eq!(
+            relative_files(&workspace),
+            [
+                ".gitignore",
+                ".panthereyesignore",
+                "ios/App/Info.plist",
+                "nginx.conf",
+                "vendor/nginx.conf"
+            ]
+        );
+
+        let options = WorkspaceOptions {
+            include: vec!["**/*.conf".to_string(), "**/*.plist".to_string()],
+            exclude: vec!["vendor/**".to_string()],
+            ..WorkspaceOptions::default()
+        };
+        let workspace = Workspace::discover(root.path(), &options).expect("walk");
+        assert_eq!(
+            relative_files(&workspace),
+            ["ios/App/Info.plist", "nginx.conf"]
+        );
+    }
+
+    #[test]
+    fn skips_binary_and_oversized_files_with_warnings() {
+        let root = TempDir::new("panthereyes-workspace-skip");
+        write_file(&root.join("small.conf"), b"server {}");
+        write_file(&root.join("logo.png"), b"\x89PNG\r\n\x1a\n\0\0\0");
+        write_file(&root.join("huge.conf"), &[b'#'; 64]);
+
+        let options = WorkspaceOptions {
+            max_file_size: 32,
+            ..WorkspaceOptions::default()
+        };
+        let workspace = Workspace::discover(root.path(), &options).expect("walk");
+        assert_eq!(relative_files(&workspace), ["small.conf"]);
+        assert_eq!(workspace.skipped().len(), 2);
+
+        let warnings = workspace.warnings();
+        assert_eq!(warnings.len(), 1, "binary files are skipped silently");
+        assert_eq!(warnings[0].code, "workspace.too-large");
+        assert_eq!(warnings[0].subject.as_deref(), Some("huge.conf"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn survives_symlink_loops_and_skips_links_leaving_the_root() {
+        use std::os::unix::fs::symlink;
+
+        let root = TempDir::new("panthereyes-workspace-symlink");
+        let outside = TempDir::new("panthereyes-workspace-outside");
+        write_file(&root.join("app/nginx.conf"), b"server {}");
+        write_file(&outside.join("secret.conf"), b"server {}");
+        symlink(root.path(), root.join("app/loop")).expect("loop symlink");
+        symlink(outside.join("secret.conf"), root.join("escape.conf")).expect("escape symlink");
+        symlink(outside.path(), root.join("app/linked")).expect("directory symlink");
+
+        let workspace =
+            Workspace::discover(root.path(), &WorkspaceOptions::default()).expect("walk");
+        assert_eq!(relative_files(&workspace), ["app/nginx.conf"]);
+        let reasons: Vec<&SkipReason> = workspace
+            .skipped()
+            .iter()
+            .map(|entry| &entry.reason)
+            .collect();
+        assert!(reasons.contains(&&SkipReason::SymlinkLoop));
+        let outside_root: Vec<&Path> = workspace
+            .skipped()
+            .iter()
+            .filter(|entry| entry.reason == SkipReason::SymlinkOutsideRoot)
+            .map(|entry| entry.path.as_path())
+            .collect();
+        assert_eq!(
+            outside_root,
+            [
+                root.join("app/linked").as_path(),
+                root.join("escape.conf").as_path()
+            ]
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use panthereyes_core::{CheckMetadata, Finding, Severity};
+use serde::{Deserialize, Serialize};
+
+use crate::{config_file, PolicyError, PolicyTarget};
+
+pub const RULES_FILE_NAME: &str = "rules.yaml";
+
+const KIND: &str = "rule catalog";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RulesFile {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub rules: Vec<RuleMetadata>,
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleMetadata {
+    pub rule_id: String,
+    pub title: String,
+    pub description: String,
+    pub default_severity: Severity,
+    pub remediation: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub allow_exception: bool,
+    #[serde(default = "default_targets")]
+    pub targets: Vec<PolicyTarget>,
+}
+
+fn default_targets() -> Vec<PolicyTarget> {
+    vec![PolicyTarget::Web, PolicyTarget::Mobile]
+}
+
+/// Cross-check between the catalog and the checks compiled into the engines.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CatalogValidation {
+    /// Catalog rules that no registered check implements.
+    pub unimplemented_rules: Vec<String>,
+    /// Registered checks whose rule (and finding id) is missing from the catalog.
+    pub uncataloged_checks: Vec<String>,
+}
+
+impl CatalogValidation {
+    pub fn is_consistent(&self) -> bool {
+        self.unimplemented_rules.is_empty() && self.uncataloged_checks.is_empty()
+    }
+}
+
+impl RulesFile {
+    pub fn rule(&self, rule_id: &str) -> Option<&RuleMetadata> {
+        self.rules.iter().find(|rule| rule.rule_id == rule_id)
+    }
+
+    /// Catalog entry for a finding, looked up by catalog rule id first and
+    /// then by the finding id (catalogs may list check ids directly).
+    pub fn rule_for(&self, finding: &Finding) -> Option<&RuleMetadata> {
+        finding
+            .rule_id
+            .as_deref()
+            .and_then(|rule_id| self.rule(rule_id))
+            .or_else(|| self.rule(&finding.id))
+    }
+
+    /// Findings without a catalog entry are not restricted.
+    pub fn allows_exception(&self, finding: &Finding) -> bool {
+        self.rule_for(finding)
+            .is_none_or(|rule| rule.allow_exception)
+    }
+
+    /// Checks are only expected in the catalog when it covers their target,
+    /// so a mobile-only catalog does not flag the web checks.
+    pub fn validate_against(&self, checks: &[CheckMetadata]) -> CatalogValidation {
+        let covered: BTreeSet<PolicyTarget> = self
+            .rules
+            .iter()
+            .flat_map(|rule| rule.targets.iter().copied())
+            .collect();
+        let unimplemented_rules = self
+            .rules
+            .iter()
+            .filter(|rule| !checks.iter().any(|check| check.implements(&rule.rule_id)))
+            .map(|rule| rule.rule_id.clone())
+            .collect();
+        let uncataloged_checks = checks
+            .iter()
+            .filter(|check| covered.contains(&PolicyTarget::from(check.target)))
+            .filter(|check| {
+                !self
+                    .rules
+                    .iter()
+                    .any(|rule| check.implements(&rule.rule_id))
+            })
+            .map(|check| check.id.to_string())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+
+        CatalogValidation {
+            unimplemented_rules,
+            uncataloged_checks,
+        }
+    }
+
+    fn validate(&self, path: &Path) -> Result<(), PolicyError> {
+        if self.version == 0 {
+            return Err(PolicyError::schema(
+                KIND,
+                path,
+                "version must be a positive integer".to_string(),
+            ));
+        }
+
+        let mut seen = BTreeSet::new();
+        for (index, rule) in self.rules.iter().enumerate() {
+            let field = |name: &str| format!("rules[{index}].{name}");
+            let required = [
+                ("ruleId", &rule.rule_id),
+                ("title", &rule.title),
+                ("description", &rule.description),
+                ("remediation", &rule.remediation),
+            ];
+            for (name, value) in required {
+                if value.trim().is_empty() {
+                    return Err(PolicyError::schema(
+                        KIND,
+                        path,
+                        format!("{} must not be empty", field(name)),
+                    ));
+                }
+            }
+            if rule.tags.iter().any(|tag| tag.is_empty()) {
+                return Err(PolicyError::schema(
+                    KIND,
+                    path,
+                    format!("{} must not contain empty tags", field("tags")),
+                ));
+            }
+            if !seen.insert(rule.rule_id.as_str()) {
+                return Err(PolicyError::schema(
+                    KIND,
+                    path,
+                    format!("duplicate ruleId '{}'", rule.rule_id),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn parse_rules_yaml(raw_yaml: &str, path: impl AsRef<Path>) -> Result<RulesFile, PolicyError> {
+    let path = path.as_ref();
+    let file: RulesFile = serde_yaml::from_str(raw_yaml).map_err(|source| PolicyError::Parse {
+        kind: KIND,
+        path: path.to_path_buf(),
+        source,
+    })?;
+    file.validate(path)?;
+    Ok(file)
+}
+
+pub fn read_rules_file(path: impl AsRef<Path>) -> Result<RulesFile, PolicyError> {
+    let path = path.as_ref();
+    let raw = fs::read_to_string(path).map_err(|source| PolicyError::Io {
+        kind: KIND,
+        path: path.to_path_buf(),
+        source,
+    })?;
+    parse_rules_yaml(&raw, path)
+}
+
+/// Loads `<root_dir>/.panthereyes/rules.yaml`.
+pub fn load_rule_catalog(root_dir: impl AsRef<Path>) -> Result<(PathBuf, RulesFile), PolicyError> {
+    let path = config_file(root_dir, RULES_FILE_NAME);
+    let file = read_rules_file(&path)?;
+    Ok((path, file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use panthereyes_core::TargetKind;
+
+    const CSP: CheckMetadata = CheckMetadata {
+        id: "web.headers.csp.missing",
+        rule_id: "web.csp.required",
+        title: "CSP",
+        description: "",
+        target: TargetKind::Web,
+        default_severity: Severity::High,
+        tags: &[],
+        allow_exception: true,
+    };
+    const LEGACY: CheckMetadata = CheckMetadata {
+        id: "web.legacy.check",
+        rule_id: "web.legacy.rule",
+        title: "Legacy",
+        description: "",
+        target: TargetKind::Web,
+        default_severity: Severity::Low,
+        tags: &[],
+        allow_exception: false,
+    };
+
+    #[test]
+    fn loads_repo_catalog_and_applies_defaults() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let (_, catalog) = load_rule_catalog(root).expect("repo catalog should load");
+        let csp = catalog.rule("web.csp.required").expect("csp rule");
+        assert_eq!(csp.default_severity, Severity::High);
+        assert!(csp.allow_exception);
+        assert_eq!(csp.targets, [PolicyTarget::Web]);
+
+        let minimal = parse_rules_yaml(
+            "rules:\n  - ruleId: r\n    title: t\n    description: d\n    defaultSeverity: low\n    remediation: fix\n",
+            "rules.yaml",
+        )
+        .expect("parse");
+        assert_eq!(minimal.version, 1);
+        assert!(!minimal.rules[0].allow_exception);
+        assert_eq!(
+            minimal.rules[0].targets,
+            [PolicyTarget::Web, PolicyTarget::Mobile]
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_rule_ids() {
+        let rule = "  - ruleId: r\n    title: t\n    description: d\n    defaultSeverity: low\n    remediation: fix\n";
+        let err = parse_rules_yaml(&format!("rules:\n{rule}{rule}"), "rules.yaml")
+            .expect_err("duplicate");
+        assert!(err.to_string().contains("duplicate ruleId 'r'"));
+    }
+
+    #[test]
+    fn validation_reports_gaps_in_both_directions() {
+        let catalog = parse_rules_yaml(
+            r#"
+rules:
+  - ruleId: web.csp.required
+    title: CSP
+    description: d
+    defaultSeverity: high
+    remediation: fix
+  - ruleId: web.cookies.secure-flag
+    title: Cookies
+    description: d
+    defaultSeverity: medium
+    remediation: fix
+"#,
+            "rules.yaml",
+        )
+        .expect("parse");
+
+        let validation = catalog.validate_against(&[CSP, LEGACY]);
+        assert_eq!(validation.unimplemented_rules, ["web.cookies.secure-flag"]);
+        assert_eq!(validation.uncataloged_checks, ["web.legacy.check"]);
+        assert!(!validation.is_consistent());
+    }
+
+    #[test]
+    fn finds_rules_by_catalog_id_or_check_id() {
+        let catalog = parse_rules_yaml(
+            "rules:\n  - ruleId: web.legacy.check\n    title: t\n    description: d\n    defaultSeverity: low\n    remediation: fix\n",
+            "rules.yaml",
+        )
+        .expect("parse");
+
+        let by_check_id = Finding::for_check(&LEGACY, "m", "r");
+        assert_eq!(
+            catalog
+                .rule_for(&by_check_id)
+                .map(|rule| rule.rule_id.as_str()),
+            Some("web.legacy.check")
+        );
+        assert!(!catalog.allows_exception(&by_check_id));
+        assert!(catalog.allows_exception(&Finding::for_check(&CSP, "m", "r")));
+        assert!(catalog.validate_against(&[LEGACY]).is_consistent());
+    }
+}
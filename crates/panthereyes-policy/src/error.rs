@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PolicyError {
+    #[error("failed to read {kind} file {path}")]
+    Io {
+        kind: &'static str,
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("invalid {kind} schema in {path}: {source}")]
+    Parse {
+        kind: &'static str,
+        path: PathBuf,
+        #[source]
+        source: serde_yaml::Error,
+    },
+
+    #[error("invalid {kind} schema in {path}: {message}")]
+    Schema {
+        kind: &'static str,
+        path: PathBuf,
+        message: String,
+    },
+
+    #[error("unknown policy environment '{env}'. Available: {available}")]
+    UnknownEnvironment { env: String, available: String },
+}
+
+impl PolicyError {
+    pub(crate) fn schema(kind: &'static str, path: impl Into<PathBuf>, message: String) -> Self {
+        Self::Schema {
+            kind,
+            path: path.into(),
+            message,
+        }
+    }
+}
@@ -0,0 +1,540 @@
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use globset::{GlobBuilder, GlobMatcher};
+use panthereyes_core::{Finding, ScanReport, ScanWarning};
+use serde::{Deserialize, Serialize};
+
+use crate::{config_file, PolicyError, PolicyTarget, RulesFile};
+
+pub const EXCEPTIONS_FILE_NAME: &str = "exceptions.yaml";
+/// Exceptions expiring within this many days are reported as warnings.
+pub const EXPIRY_WARNING_DAYS: i64 = 30;
+
+const KIND: &str = "exceptions";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExceptionsFile {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    pub exceptions: Vec<PolicyException>,
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyException {
+    pub exception_id: String,
+    pub rule_id: String,
+    pub environments: Vec<String>,
+    pub targets: Vec<PolicyTarget>,
+    pub reason: String,
+    pub approved_by: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_on: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<ExceptionScope>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExceptionScope {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub services: Vec<String>,
+}
+
+/// Calendar day (UTC) used for `expiresOn` comparisons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CalendarDate {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+}
+
+impl CalendarDate {
+    pub fn parse(raw: &str) -> Option<Self> {
+        let mut parts = raw.split('-');
+        let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+        if parts.next().is_some() || year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return None;
+        }
+
+        let date = Self {
+            year: year.parse().ok()?,
+            month: month.parse().ok()?,
+            day: day.parse().ok()?,
+        };
+        let valid = (1..=12).contains(&date.month)
+            && date.day >= 1
+            && date.day <= days_in_month(date.year, date.month);
+        valid.then_some(date)
+    }
+
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self::from_days_since_epoch((seconds / 86_400) as i64)
+    }
+
+    // Civil-from-days conversion (Howard Hinnant's algorithm).
+    fn from_days_since_epoch(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Self { year, month, day }
+    }
+
+    fn days_since_epoch(self) -> i64 {
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = i64::from(self.month);
+        let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5
+            + i64::from(self.day)
+            - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    pub fn days_until(self, other: Self) -> i64 {
+        other.days_since_epoch() - self.days_since_epoch()
+    }
+}
+
+impl Display for CalendarDate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl PolicyException {
+    pub fn expiry(&self) -> Option<CalendarDate> {
+        self.expires_on.as_deref().and_then(CalendarDate::parse)
+    }
+
+    /// Active on `today` when there is no expiry or the expiry day has not passed.
+    pub fn is_active_on(&self, today: CalendarDate) -> bool {
+        self.expiry().is_none_or(|expiry| expiry >= today)
+    }
+
+    pub fn applies_to(&self, env: &str, target: PolicyTarget) -> bool {
+        self.environments.iter().any(|candidate| candidate == env) && self.targets.contains(&target)
+    }
+
+    fn path_matchers(&self) -> Vec<GlobMatcher> {
+        self.scope
+            .iter()
+            .flat_map(|scope| &scope.paths)
+            .filter_map(|pattern| compile_glob(pattern))
+            .collect()
+    }
+
+    fn validate(&self, index: usize, path: &Path) -> Result<(), PolicyError> {
+        let field = |name: &str| format!("exceptions[{index}].{name}");
+        let required = [
+            ("exceptionId", &self.exception_id),
+            ("ruleId", &self.rule_id),
+            ("reason", &self.reason),
+            ("approvedBy", &self.approved_by),
+        ];
+        for (name, value) in required {
+            if value.trim().is_empty() {
+                return Err(PolicyError::schema(
+                    KIND,
+                    path,
+                    format!("{} must not be empty", field(name)),
+                ));
+            }
+        }
+        if self.environments.is_empty() || self.environments.iter().any(|env| env.is_empty()) {
+            return Err(PolicyError::schema(
+                KIND,
+                path,
+                format!(
+                    "{} must list at least one environment",
+                    field("environments")
+                ),
+            ));
+        }
+        if self.targets.is_empty() {
+            return Err(PolicyError::schema(
+                KIND,
+                path,
+                format!("{} must list at least one target", field("targets")),
+            ));
+        }
+        if let Some(raw) = &self.expires_on {
+            if CalendarDate::parse(raw).is_none() {
+                return Err(PolicyError::schema(
+                    KIND,
+                    path,
+                    format!(
+                        "{} must be a YYYY-MM-DD date, got '{raw}'",
+                        field("expiresOn")
+                    ),
+                ));
+            }
+        }
+        for pattern in self.scope.iter().flat_map(|scope| &scope.paths) {
+            if compile_glob(pattern).is_none() {
+                return Err(PolicyError::schema(
+                    KIND,
+                    path,
+                    format!("{} has an invalid glob '{pattern}'", field("scope.paths")),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn compile_glob(pattern: &str) -> Option<GlobMatcher> {
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .ok()
+        .map(|glob| glob.compile_matcher())
+}
+
+fn normalize_relative(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('\\', "/")
+        .trim_start_matches("./")
+        .to_string()
+}
+
+/// Active exceptions for one env+target with their compiled path scopes.
+pub struct ExceptionMatcher<'a> {
+    env: &'a str,
+    target: PolicyTarget,
+    today: CalendarDate,
+    catalog: Option<&'a RulesFile>,
+    entries: Vec<(&'a PolicyException, Vec<GlobMatcher>)>,
+}
+
+impl<'a> ExceptionMatcher<'a> {
+    pub fn new(
+        file: &'a ExceptionsFile,
+        env: &'a str,
+        target: impl Into<PolicyTarget>,
+        today: CalendarDate,
+    ) -> Self {
+        let target = target.into();
+        let entries = file
+            .exceptions
+            .iter()
+            .filter(|entry| entry.applies_to(env, target))
+            .map(|entry| (entry, entry.path_matchers()))
+            .collect();
+
+        Self {
+            env,
+            target,
+            today,
+            catalog: None,
+            entries,
+        }
+    }
+
+    /// Refuses exceptions for rules the catalog marks with `allowException: false`.
+    pub fn with_catalog(mut self, catalog: &'a RulesFile) -> Self {
+        self.catalog = Some(catalog);
+        self
+    }
+
+    /// First active exception covering `finding`.
+    pub fn find(&self, finding: &Finding) -> Option<&'a PolicyException> {
+        self.entries
+            .iter()
+            .filter(|(entry, _)| entry.is_active_on(self.today))
+            .filter(|(entry, _)| finding.matches_rule(&entry.rule_id))
+            .find(|(_, matchers)| {
+                if matchers.is_empty() {
+                    return true;
+                }
+                let Some(file) = finding.file.as_deref() else {
+                    return false;
+                };
+                let file = normalize_relative(file);
+                matchers.iter().any(|matcher| matcher.is_match(&file))
+            })
+            .map(|(entry, _)| *entry)
+    }
+
+    /// Marks covered findings as excepted, recomputes status and appends
+    /// warnings for expired or soon-to-expire exceptions in scope and for
+    /// exceptions the rule catalog does not allow.
+    pub fn apply(&self, report: &mut ScanReport) {
+        let mut refused: Vec<(&PolicyException, String)> = Vec::new();
+        for finding in &mut report.findings {
+            let Some(entry) = self.find(finding) else {
+                continue;
+            };
+            if self
+                .catalog
+                .is_none_or(|catalog| catalog.allows_exception(finding))
+            {
+                finding.exception = Some(entry.exception_id.clone());
+            } else if !refused
+                .iter()
+                .any(|(seen, _)| seen.exception_id == entry.exception_id)
+            {
+                refused.push((entry, finding.id.clone()));
+            }
+        }
+
+        for (entry, finding_id) in refused {
+            report.warnings.push(
+                ScanWarning::new(
+                    "exception.not-allowed",
+                    format!(
+                        "Excecao {} ignorada: a regra de {finding_id} nao permite excecoes (allowException=false).",
+                        entry.exception_id
+                    ),
+                )
+                .with_subject(entry.exception_id.clone()),
+            );
+        }
+
+        for (entry, _) in &self.entries {
+            let Some(expiry) = entry.expiry() else {
+                continue;
+            };
+            let days_left = self.today.days_until(expiry);
+            let warning = if days_left < 0 {
+                ScanWarning::new(
+                    "exception.expired",
+                    format!(
+                        "Excecao {} ({}) expirou em {expiry} e nao esta mais sendo aplicada em {}/{}.",
+                        entry.exception_id, entry.rule_id, self.env, self.target
+                    ),
+                )
+            } else if days_left <= EXPIRY_WARNING_DAYS {
+                ScanWarning::new(
+                    "exception.expiring-soon",
+                    format!(
+                        "Excecao {} ({}) expira em {expiry} ({days_left} dia(s)); renove ou corrija o achado.",
+                        entry.exception_id, entry.rule_id
+                    ),
+                )
+            } else {
+                continue;
+            };
+            report
+                .warnings
+                .push(warning.with_subject(entry.exception_id.clone()));
+        }
+
+        report.recompute_status();
+    }
+}
+
+pub fn parse_exceptions_yaml(
+    raw_yaml: &str,
+    path: impl AsRef<Path>,
+) -> Result<ExceptionsFile, PolicyError> {
+    let path = path.as_ref();
+    let file: ExceptionsFile =
+        serde_yaml::from_str(raw_yaml).map_err(|source| PolicyError::Parse {
+            kind: KIND,
+            path: path.to_path_buf(),
+            source,
+        })?;
+    if file.version == 0 {
+        return Err(PolicyError::schema(
+            KIND,
+            path,
+            "version must be a positive integer".to_string(),
+        ));
+    }
+    for (index, entry) in file.exceptions.iter().enumerate() {
+        entry.validate(index, path)?;
+    }
+    Ok(file)
+}
+
+pub fn read_exceptions_file(path: impl AsRef<Path>) -> Result<ExceptionsFile, PolicyError> {
+    let path = path.as_ref();
+    let raw = fs::read_to_string(path).map_err(|source| PolicyError::Io {
+        kind: KIND,
+        path: path.to_path_buf(),
+        source,
+    })?;
+    parse_exceptions_yaml(&raw, path)
+}
+
+/// Loads `<root_dir>/.panthereyes/exceptions.yaml`.
+pub fn load_exceptions_file(
+    root_dir: impl AsRef<Path>,
+) -> Result<(PathBuf, ExceptionsFile), PolicyError> {
+    let path = config_file(root_dir, EXCEPTIONS_FILE_NAME);
+    let file = read_exceptions_file(&path)?;
+    Ok((path, file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use panthereyes_core::{ScanStatus, Severity, TargetKind};
+
+    const EXCEPTIONS: &str = r#"
+version: 1
+exceptions:
+  - exceptionId: EXC-001
+    ruleId: web.headers.csp.missing
+    environments: [dev]
+    targets: [web]
+    reason: Legacy app without header proxy.
+    approvedBy: security-team
+    expiresOn: 2026-11-01
+    scope:
+      paths:
+        - apps/legacy-web/**
+  - exceptionId: EXC-002
+    ruleId: web.headers.hsts.missing
+    environments: [dev]
+    targets: [web]
+    reason: Expired waiver.
+    approvedBy: security-team
+    expiresOn: 2026-01-31
+"#;
+
+    fn date(raw: &str) -> CalendarDate {
+        CalendarDate::parse(raw).expect("valid date")
+    }
+
+    fn finding(id: &str, file: &str) -> Finding {
+        Finding::new(id, id, Severity::High, TargetKind::Web, "message", "fix").with_file(file)
+    }
+
+    #[test]
+    fn marks_scoped_findings_and_keeps_them_in_report() {
+        let file = parse_exceptions_yaml(EXCEPTIONS, "exceptions.yaml").expect("parse");
+        let mut report = ScanReport::new(TargetKind::Web);
+        report.push(finding(
+            "web.headers.csp.missing",
+            "apps/legacy-web/nginx/site.conf",
+        ));
+        report.push(finding("web.headers.csp.missing", "apps/web/nginx.conf"));
+        report.push(finding("web.headers.hsts.missing", "apps/web/nginx.conf"));
+
+        ExceptionMatcher::new(&file, "dev", TargetKind::Web, date("2026-10-17")).apply(&mut report);
+
+        assert_eq!(report.findings.len(), 3);
+        assert_eq!(report.findings[0].exception.as_deref(), Some("EXC-001"));
+        assert_eq!(report.findings[1].exception, None);
+        assert_eq!(
+            report.findings[2].exception, None,
+            "expired exceptions must not apply"
+        );
+        assert_eq!(report.status, ScanStatus::Block);
+
+        let codes: Vec<&str> = report
+            .warnings
+            .iter()
+            .map(|warning| warning.code.as_str())
+            .collect();
+        assert_eq!(codes, ["exception.expiring-soon", "exception.expired"]);
+    }
+
+    #[test]
+    fn excepted_findings_do_not_gate_and_other_envs_are_ignored() {
+        let file = parse_exceptions_yaml(EXCEPTIONS, "exceptions.yaml").expect("parse");
+        let legacy = finding("web.headers.csp.missing", "apps/legacy-web/site.conf");
+
+        let mut dev = ScanReport::new(TargetKind::Web);
+        dev.push(legacy.clone());
+        ExceptionMatcher::new(&file, "dev", TargetKind::Web, date("2025-06-01")).apply(&mut dev);
+        assert_eq!(dev.status, ScanStatus::Pass);
+        assert!(dev.warnings.is_empty());
+
+        let mut prod = ScanReport::new(TargetKind::Web);
+        prod.push(legacy);
+        ExceptionMatcher::new(&file, "prod", TargetKind::Web, date("2025-06-01")).apply(&mut prod);
+        assert_eq!(prod.findings[0].exception, None);
+        assert_eq!(prod.status, ScanStatus::Block);
+    }
+
+    #[test]
+    fn matches_catalog_rule_ids_and_honors_allow_exception() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let (_, file) = load_exceptions_file(&root).expect("repo exceptions");
+        let (_, catalog) = crate::load_rule_catalog(&root).expect("repo catalog");
+
+        let mut web = ScanReport::new(TargetKind::Web);
+        web.push(
+            finding("web.headers.csp.missing", "apps/legacy-web/nginx.conf")
+                .with_rule("web.csp.required"),
+        );
+        ExceptionMatcher::new(&file, "dev", TargetKind::Web, date("2026-10-17"))
+            .with_catalog(&catalog)
+            .apply(&mut web);
+        assert_eq!(web.findings[0].exception.as_deref(), Some("EXC-001"));
+
+        let mut mobile = ScanReport::new(TargetKind::Android);
+        mobile.push(
+            finding("mobile.android.debuggable-enabled", "AndroidManifest.xml")
+                .with_rule("mobile.debug.disabled"),
+        );
+        ExceptionMatcher::new(&file, "dev", TargetKind::Android, date("2026-10-17"))
+            .with_catalog(&catalog)
+            .apply(&mut mobile);
+        assert_eq!(mobile.findings[0].exception, None);
+        assert_eq!(mobile.warnings[0].code, "exception.not-allowed");
+        assert_eq!(mobile.warnings[0].subject.as_deref(), Some("EXC-002"));
+    }
+
+    #[test]
+    fn validates_schema_and_parses_repo_file() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let (_, file) = load_exceptions_file(root).expect("repo exceptions should load");
+        assert_eq!(file.exceptions.len(), 2);
+
+        let err = parse_exceptions_yaml(
+            "exceptions:\n  - exceptionId: X\n    ruleId: r\n    environments: [dev]\n    targets: [web]\n    reason: r\n    approvedBy: a\n    expiresOn: 2026-02-30\n",
+            "exceptions.yaml",
+        )
+        .expect_err("invalid date");
+        assert!(err.to_string().contains("expiresOn"));
+    }
+
+    #[test]
+    fn calendar_dates_round_trip_through_epoch_days() {
+        let date = date("2024-02-29");
+        assert_eq!(
+            CalendarDate::from_days_since_epoch(date.days_since_epoch()),
+            date
+        );
+        assert_eq!(
+            CalendarDate::from_days_since_epoch(0),
+            self::date("1970-01-01")
+        );
+        assert_eq!(
+            self::date("2026-10-17").days_until(self::date("2026-11-01")),
+            15
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use std::path::{Path, PathBuf};
+
+mod catalog;
+mod error;
+mod exceptions;
+mod policy;
+
+pub use catalog::{
+    load_rule_catalog, parse_rules_yaml, read_rules_file, CatalogValidation, RuleMetadata,
+    RulesFile, RULES_FILE_NAME,
+};
+pub use error::PolicyError;
+pub use exceptions::{
+    load_exceptions_file, parse_exceptions_yaml, read_exceptions_file, CalendarDate,
+    ExceptionMatcher, ExceptionScope, ExceptionsFile, PolicyException, EXCEPTIONS_FILE_NAME,
+    EXPIRY_WARNING_DAYS,
+};
+pub use policy::{
+    load_policy_file, parse_policy_yaml, read_policy_file, DirectiveValue, EffectiveDirective,
+    EffectivePolicy, EnvironmentPolicy, EnvironmentTargets, PolicyFile, PolicyLayer, PolicyTarget,
+    RuleOverride, DEFAULT_FAIL_ON_SEVERITY, DEFAULT_MODE, FAIL_ON_DIAGNOSTICS_DIRECTIVE,
+    MIN_SCORE_DIRECTIVE, POLICY_FILE_NAME, SCORE_WEIGHT_DIRECTIVES,
+};
+
+pub const CONFIG_DIR: &str = ".panthereyes";
+
+/// Path of a PantherEyes configuration file under `<root_dir>/.panthereyes/`.
+pub fn config_file(root_dir: impl AsRef<Path>, file_name: &str) -> PathBuf {
+    root_dir.as_ref().join(CONFIG_DIR).join(file_name)
+}
@@ -0,0 +1,689 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use panthereyes_core::{
+    Finding, PolicyMode, PolicyResult, ScanReport, ScoreWeights, Severity, TargetKind, MAX_SCORE,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{config_file, PolicyError};
+
+pub const POLICY_FILE_NAME: &str = "policy.yaml";
+pub const DEFAULT_MODE: PolicyMode = PolicyMode::Warn;
+pub const DEFAULT_FAIL_ON_SEVERITY: Severity = Severity::High;
+
+const KIND: &str = "policy";
+
+pub const MIN_SCORE_DIRECTIVE: &str = "minScore";
+/// Boolean directive: block when a check failed instead of only reporting it.
+pub const FAIL_ON_DIAGNOSTICS_DIRECTIVE: &str = "failOnDiagnostics";
+/// Directives overriding `ScoreWeights`, in low/medium/high/critical order.
+pub const SCORE_WEIGHT_DIRECTIVES: [&str; 4] = [
+    "scoreWeightLow",
+    "scoreWeightMedium",
+    "scoreWeightHigh",
+    "scoreWeightCritical",
+];
+
+/// Targets addressable from `envs.<env>.targets` in the policy file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyTarget {
+    Web,
+    Mobile,
+}
+
+impl PolicyTarget {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Web => "web",
+            Self::Mobile => "mobile",
+        }
+    }
+}
+
+impl From<TargetKind> for PolicyTarget {
+    fn from(target: TargetKind) -> Self {
+        match target {
+            TargetKind::Web => Self::Web,
+            TargetKind::Mobile | TargetKind::Ios | TargetKind::Android => Self::Mobile,
+        }
+    }
+}
+
+impl Display for PolicyTarget {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Directive values are scalars or flat lists of scalars.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DirectiveValue {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    List(Vec<DirectiveValue>),
+}
+
+impl DirectiveValue {
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::Integer(value) => Some(*value as f64),
+            Self::Float(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn is_scalar(&self) -> bool {
+        !matches!(self, Self::List(_))
+    }
+}
+
+impl Display for DirectiveValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{value}"),
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::String(value) => f.write_str(value),
+            Self::List(values) => {
+                let rendered: Vec<String> = values.iter().map(ToString::to_string).collect();
+                write!(f, "[{}]", rendered.join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub directives: BTreeMap<String, DirectiveValue>,
+}
+
+impl RuleOverride {
+    fn merge(&mut self, incoming: &RuleOverride) {
+        if incoming.enabled.is_some() {
+            self.enabled = incoming.enabled;
+        }
+        if incoming.severity.is_some() {
+            self.severity = incoming.severity;
+        }
+        self.directives.extend(
+            incoming
+                .directives
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PolicyLayer {
+    #[serde(default)]
+    pub mode: Option<PolicyMode>,
+    #[serde(default)]
+    pub fail_on_severity: Option<Severity>,
+    #[serde(default)]
+    pub directives: BTreeMap<String, DirectiveValue>,
+    #[serde(default)]
+    pub rule_overrides: BTreeMap<String, RuleOverride>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct EnvironmentTargets {
+    #[serde(default)]
+    pub web: Option<PolicyLayer>,
+    #[serde(default)]
+    pub mobile: Option<PolicyLayer>,
+}
+
+impl EnvironmentTargets {
+    pub fn get(&self, target: PolicyTarget) -> Option<&PolicyLayer> {
+        match target {
+            PolicyTarget::Web => self.web.as_ref(),
+            PolicyTarget::Mobile => self.mobile.as_ref(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct EnvironmentPolicy {
+    #[serde(flatten)]
+    pub layer: PolicyLayer,
+    #[serde(default)]
+    pub targets: EnvironmentTargets,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PolicyFile {
+    #[serde(default = "default_version")]
+    pub version: u32,
+    #[serde(default)]
+    pub defaults: PolicyLayer,
+    #[serde(default)]
+    pub envs: BTreeMap<String, EnvironmentPolicy>,
+}
+
+fn default_version() -> u32 {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffectiveDirective {
+    pub key: String,
+    pub value: DirectiveValue,
+    /// Layer that last set the value: `defaults`, `envs.<env>` or `envs.<env>.targets.<target>`.
+    pub source: String,
+}
+
+/// Policy for one env+target after merging `defaults -> envs.<env> -> envs.<env>.targets.<target>`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EffectivePolicy {
+    pub env: String,
+    pub target: PolicyTarget,
+    pub mode: PolicyMode,
+    pub fail_on_severity: Severity,
+    pub directives: BTreeMap<String, DirectiveValue>,
+    pub directive_list: Vec<EffectiveDirective>,
+    pub rule_overrides: BTreeMap<String, RuleOverride>,
+}
+
+impl EffectivePolicy {
+    pub fn directive(&self, key: &str) -> Option<&DirectiveValue> {
+        self.directives.get(key)
+    }
+
+    pub fn rule_override(&self, rule_id: &str) -> Option<&RuleOverride> {
+        self.rule_overrides.get(rule_id)
+    }
+
+    /// Override for a finding, keyed by its finding id first and then by its catalog rule id.
+    pub fn rule_override_for(&self, finding: &Finding) -> Option<&RuleOverride> {
+        self.rule_override(&finding.id).or_else(|| {
+            finding
+                .rule_id
+                .as_deref()
+                .and_then(|rule_id| self.rule_override(rule_id))
+        })
+    }
+
+    /// Rule ids with `enabled: false`, to hand to `ScanRequest::with_disabled_checks`.
+    pub fn disabled_rules(&self) -> Vec<&str> {
+        self.rule_overrides
+            .iter()
+            .filter(|(_, rule)| rule.enabled == Some(false))
+            .map(|(rule_id, _)| rule_id.as_str())
+            .collect()
+    }
+
+    /// Drops findings of disabled rules and rewrites overridden severities
+    /// (keeping the original on the finding), then recomputes the status.
+    pub fn apply_rule_overrides(&self, report: &mut ScanReport) {
+        report.findings.retain(|finding| {
+            self.rule_override_for(finding)
+                .is_none_or(|rule| rule.enabled != Some(false))
+        });
+        for finding in &mut report.findings {
+            if let Some(severity) = self
+                .rule_override_for(finding)
+                .and_then(|rule| rule.severity)
+            {
+                finding.override_severity(severity);
+            }
+        }
+        report.recompute_status();
+    }
+
+    /// Gating parameters to hand to `ScanReport::with_policy`.
+    pub fn policy_result(&self) -> PolicyResult {
+        PolicyResult {
+            blocking_severity: self.fail_on_severity,
+            mode: self.mode,
+            min_score: self.min_score(),
+            score_weights: self.score_weights(),
+            fail_on_diagnostics: self.fail_on_diagnostics(),
+            ..PolicyResult::default()
+        }
+    }
+
+    pub fn min_score(&self) -> Option<u8> {
+        self.directive(MIN_SCORE_DIRECTIVE)
+            .and_then(DirectiveValue::as_f64)
+            .map(|value| value.clamp(0.0, f64::from(MAX_SCORE)).round() as u8)
+    }
+
+    pub fn fail_on_diagnostics(&self) -> bool {
+        self.directive(FAIL_ON_DIAGNOSTICS_DIRECTIVE)
+            .and_then(DirectiveValue::as_bool)
+            .unwrap_or(false)
+    }
+
+    pub fn score_weights(&self) -> ScoreWeights {
+        let defaults = ScoreWeights::default();
+        let [low, medium, high, critical] = SCORE_WEIGHT_DIRECTIVES;
+        let weight = |key: &str, default: u32| {
+            self.directive(key)
+                .and_then(DirectiveValue::as_f64)
+                .map_or(default, |value| value.max(0.0).round() as u32)
+        };
+        ScoreWeights {
+            low: weight(low, defaults.low),
+            medium: weight(medium, defaults.medium),
+            high: weight(high, defaults.high),
+            critical: weight(critical, defaults.critical),
+        }
+    }
+}
+
+impl PolicyFile {
+    pub fn env_names(&self) -> Vec<&str> {
+        self.envs.keys().map(String::as_str).collect()
+    }
+
+    pub fn resolve(
+        &self,
+        env: &str,
+        target: impl Into<PolicyTarget>,
+    ) -> Result<EffectivePolicy, PolicyError> {
+        let target = target.into();
+        let env_config = self
+            .envs
+            .get(env)
+            .ok_or_else(|| PolicyError::UnknownEnvironment {
+                env: env.to_string(),
+                available: if self.envs.is_empty() {
+                    "<none>".to_string()
+                } else {
+                    self.env_names().join(", ")
+                },
+            })?;
+
+        let layers = [
+            ("defaults".to_string(), Some(&self.defaults)),
+            (format!("envs.{env}"), Some(&env_config.layer)),
+            (
+                format!("envs.{env}.targets.{target}"),
+                env_config.targets.get(target),
+            ),
+        ];
+
+        let mut mode = DEFAULT_MODE;
+        let mut fail_on_severity = DEFAULT_FAIL_ON_SEVERITY;
+        let mut directives = BTreeMap::new();
+        let mut sources = BTreeMap::new();
+        let mut rule_overrides: BTreeMap<String, RuleOverride> = BTreeMap::new();
+
+        for (source, layer) in layers {
+            let Some(layer) = layer else {
+                continue;
+            };
+
+            if let Some(layer_mode) = layer.mode {
+                mode = layer_mode;
+            }
+            if let Some(severity) = layer.fail_on_severity {
+                fail_on_severity = severity;
+            }
+            for (key, value) in &layer.directives {
+                directives.insert(key.clone(), value.clone());
+                sources.insert(key.clone(), source.clone());
+            }
+            for (rule_id, incoming) in &layer.rule_overrides {
+                rule_overrides
+                    .entry(rule_id.clone())
+                    .or_default()
+                    .merge(incoming);
+            }
+        }
+
+        let directive_list = directives
+            .iter()
+            .map(|(key, value)| EffectiveDirective {
+                key: key.clone(),
+                value: value.clone(),
+                source: sources
+                    .get(key)
+                    .cloned()
+                    .unwrap_or_else(|| "defaults".to_string()),
+            })
+            .collect();
+
+        Ok(EffectivePolicy {
+            env: env.to_string(),
+            target,
+            mode,
+            fail_on_severity,
+            directives,
+            directive_list,
+            rule_overrides,
+        })
+    }
+
+    fn validate(&self, path: &Path) -> Result<(), PolicyError> {
+        if self.version == 0 {
+            return Err(PolicyError::schema(
+                KIND,
+                path,
+                "version must be a positive integer".to_string(),
+            ));
+        }
+
+        let mut layers: Vec<(String, &PolicyLayer)> =
+            vec![("defaults".to_string(), &self.defaults)];
+        for (env, config) in &self.envs {
+            layers.push((format!("envs.{env}"), &config.layer));
+            for target in [PolicyTarget::Web, PolicyTarget::Mobile] {
+                if let Some(layer) = config.targets.get(target) {
+                    layers.push((format!("envs.{env}.targets.{target}"), layer));
+                }
+            }
+        }
+
+        for (source, layer) in layers {
+            let overrides = layer.rule_overrides.iter().flat_map(|(rule_id, rule)| {
+                rule.directives
+                    .iter()
+                    .map(move |(key, value)| (format!("ruleOverrides.{rule_id}.{key}"), value))
+            });
+            for (key, value) in &layer.directives {
+                let number = value.as_f64();
+                let valid = if key == MIN_SCORE_DIRECTIVE {
+                    number.is_some_and(|value| (0.0..=f64::from(MAX_SCORE)).contains(&value))
+                } else if SCORE_WEIGHT_DIRECTIVES.contains(&key.as_str()) {
+                    number.is_some_and(|value| value >= 0.0)
+                } else if key == FAIL_ON_DIAGNOSTICS_DIRECTIVE {
+                    value.as_bool().is_some()
+                } else {
+                    true
+                };
+                if !valid {
+                    let expected = if key == MIN_SCORE_DIRECTIVE {
+                        "a number between 0 and 100"
+                    } else if key == FAIL_ON_DIAGNOSTICS_DIRECTIVE {
+                        "a boolean"
+                    } else {
+                        "a non-negative number"
+                    };
+                    return Err(PolicyError::schema(
+                        KIND,
+                        path,
+                        format!("{source}.directives.{key} must be {expected}, got {value}"),
+                    ));
+                }
+            }
+
+            let directives = layer
+                .directives
+                .iter()
+                .map(|(key, value)| (format!("directives.{key}"), value));
+
+            for (key, value) in directives.chain(overrides) {
+                if let DirectiveValue::List(values) = value {
+                    if !values.iter().all(DirectiveValue::is_scalar) {
+                        return Err(PolicyError::schema(
+                            KIND,
+                            path,
+                            format!("{source}.{key} must be a scalar or a list of scalars"),
+                        ));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn parse_policy_yaml(
+    raw_yaml: &str,
+    path: impl AsRef<Path>,
+) -> Result<PolicyFile, PolicyError> {
+    let path = path.as_ref();
+    let policy: PolicyFile =
+        serde_yaml::from_str(raw_yaml).map_err(|source| PolicyError::Parse {
+            kind: KIND,
+            path: path.to_path_buf(),
+            source,
+        })?;
+    policy.validate(path)?;
+    Ok(policy)
+}
+
+pub fn read_policy_file(path: impl AsRef<Path>) -> Result<PolicyFile, PolicyError> {
+    let path = path.as_ref();
+    let raw = fs::read_to_string(path).map_err(|source| PolicyError::Io {
+        kind: KIND,
+        path: path.to_path_buf(),
+        source,
+    })?;
+    parse_policy_yaml(&raw, path)
+}
+
+/// Loads `<root_dir>/.panthereyes/policy.yaml`.
+pub fn load_policy_file(root_dir: impl AsRef<Path>) -> Result<(PathBuf, PolicyFile), PolicyError> {
+    let path = config_file(root_dir, POLICY_FILE_NAME);
+    let policy = read_policy_file(&path)?;
+    Ok((path, policy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_policy() -> PolicyFile {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("../..");
+        load_policy_file(root).expect("repo policy should load").1
+    }
+
+    #[test]
+    fn resolves_dev_web_with_target_layer_winning() {
+        let effective = repo_policy()
+            .resolve("dev", TargetKind::Web)
+            .expect("dev/web");
+
+        assert_eq!(effective.mode, PolicyMode::Audit);
+        assert_eq!(effective.fail_on_severity, Severity::Critical);
+        assert_eq!(
+            effective.directive("minScore"),
+            Some(&DirectiveValue::Integer(60))
+        );
+        assert_eq!(
+            effective.directive("sampleRate"),
+            Some(&DirectiveValue::Float(0.25))
+        );
+        assert_eq!(
+            effective.directive("browserStackEnabled"),
+            Some(&DirectiveValue::Bool(true))
+        );
+
+        let csp = effective
+            .rule_override("web.csp.required")
+            .expect("csp override");
+        assert_eq!(csp.enabled, Some(true));
+        assert_eq!(csp.severity, Some(Severity::Medium));
+
+        let min_score = effective
+            .directive_list
+            .iter()
+            .find(|directive| directive.key == "minScore")
+            .expect("minScore directive");
+        assert_eq!(min_score.source, "envs.dev");
+        assert_eq!(
+            effective.policy_result().blocking_severity,
+            Severity::Critical
+        );
+    }
+
+    #[test]
+    fn rule_overrides_rewrite_severity_and_drop_disabled_rules() {
+        let mut effective = repo_policy()
+            .resolve("dev", TargetKind::Web)
+            .expect("dev/web");
+        effective.rule_overrides.insert(
+            "web.headers.hsts.missing".to_string(),
+            RuleOverride {
+                enabled: Some(false),
+                ..RuleOverride::default()
+            },
+        );
+        assert_eq!(effective.disabled_rules(), ["web.headers.hsts.missing"]);
+
+        let mut report = ScanReport::new(TargetKind::Web).with_policy(PolicyResult::default());
+        report.push(
+            Finding::new(
+                "web.headers.csp.missing",
+                "CSP",
+                Severity::High,
+                TargetKind::Web,
+                "message",
+                "fix",
+            )
+            .with_rule("web.csp.required"),
+        );
+        report.push(Finding::new(
+            "web.headers.hsts.missing",
+            "HSTS",
+            Severity::Medium,
+            TargetKind::Web,
+            "message",
+            "fix",
+        ));
+        effective.apply_rule_overrides(&mut report);
+
+        assert_eq!(report.findings.len(), 1);
+        let csp = &report.findings[0];
+        assert_eq!(csp.severity, Severity::Medium);
+        assert_eq!(csp.original_severity, Some(Severity::High));
+        assert_eq!(report.status, panthereyes_core::ScanStatus::Warn);
+    }
+
+    #[test]
+    fn resolves_prod_mobile_and_maps_platform_targets() {
+        let effective = repo_policy()
+            .resolve("prod", TargetKind::Android)
+            .expect("prod/android");
+
+        assert_eq!(effective.target, PolicyTarget::Mobile);
+        assert_eq!(effective.mode, PolicyMode::Enforce);
+        assert_eq!(effective.fail_on_severity, Severity::High);
+        assert_eq!(
+            effective.directive("minScore"),
+            Some(&DirectiveValue::Integer(95))
+        );
+        assert_eq!(
+            effective.directive("emulatorChecks"),
+            Some(&DirectiveValue::Bool(true))
+        );
+        assert_eq!(
+            effective
+                .rule_override("web.csp.required")
+                .and_then(|rule| rule.severity),
+            Some(Severity::High)
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_env_and_invalid_schema() {
+        let err = repo_policy()
+            .resolve("qa", PolicyTarget::Web)
+            .expect_err("qa is not declared");
+        assert_eq!(
+            err.to_string(),
+            "unknown policy environment 'qa'. Available: dev, prod"
+        );
+
+        let err = parse_policy_yaml("defaults:\n  mode: strict\n", "policy.yaml")
+            .expect_err("mode must be audit|warn|enforce");
+        assert!(matches!(err, PolicyError::Parse { .. }));
+
+        let err = parse_policy_yaml(
+            "defaults:\n  directives:\n    hosts: [[a, b]]\n",
+            "policy.yaml",
+        )
+        .expect_err("nested lists are not valid directives");
+        assert!(matches!(err, PolicyError::Schema { .. }));
+
+        let err = parse_policy_yaml(
+            "defaults:\n  directives:\n    minScore: 120\n",
+            "policy.yaml",
+        )
+        .expect_err("minScore is a percentage");
+        assert!(err.to_string().contains("defaults.directives.minScore"));
+    }
+
+    #[test]
+    fn min_score_and_score_weights_come_from_directives() {
+        let prod = repo_policy()
+            .resolve("prod", PolicyTarget::Web)
+            .expect("prod/web")
+            .policy_result();
+        assert_eq!(prod.min_score, Some(95));
+        assert_eq!(prod.score_weights, ScoreWeights::default());
+
+        let policy = parse_policy_yaml(
+            "envs:\n  dev:\n    directives:\n      scoreWeightHigh: 40\n",
+            "policy.yaml",
+        )
+        .expect("parse");
+        let dev = policy
+            .resolve("dev", PolicyTarget::Web)
+            .expect("dev/web")
+            .policy_result();
+        assert_eq!(dev.min_score, None);
+        assert_eq!(dev.score_weights.high, 40);
+        assert_eq!(dev.score_weights.medium, ScoreWeights::default().medium);
+        assert!(!dev.fail_on_diagnostics);
+    }
+
+    #[test]
+    fn fail_on_diagnostics_is_a_boolean_directive() {
+        let policy = parse_policy_yaml(
+            "envs:\n  prod:\n    directives:\n      failOnDiagnostics: true\n",
+            "policy.yaml",
+        )
+        .expect("parse");
+        let prod = policy
+            .resolve("prod", PolicyTarget::Mobile)
+            .expect("prod/mobile")
+            .policy_result();
+        assert!(prod.fail_on_diagnostics);
+
+        let err = parse_policy_yaml(
+            "defaults:\n  directives:\n    failOnDiagnostics: sometimes\n",
+            "policy.yaml",
+        )
+        .expect_err("failOnDiagnostics is a boolean");
+        assert!(err.to_string().contains("must be a boolean"));
+    }
+
+    #[test]
+    fn empty_policy_uses_engine_defaults() {
+        let policy = parse_policy_yaml("envs:\n  dev: {}\n", "policy.yaml").expect("parse");
+        let effective = policy
+            .resolve("dev", PolicyTarget::Mobile)
+            .expect("resolve");
+
+        assert_eq!(policy.version, 1);
+        assert_eq!(effective.mode, DEFAULT_MODE);
+        assert_eq!(effective.fail_on_severity, DEFAULT_FAIL_ON_SEVERITY);
+        assert!(effective.directives.is_empty());
+    }
+}